@@ -0,0 +1,158 @@
+//! Procedural macros of the `array` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Error, ItemFn, LitStr, Result};
+
+/// Register a function in the global function registry of the `array` crate.
+///
+/// The signature is given as `name(arg, ...) -> ret`, with types named as in
+/// `boolean, int16, int32, int64, float32, float64, decimal, date, time, timestamp, interval,
+/// varchar, bytea`. The function takes the reference type of each argument, such as `&str` for
+/// `varchar`, and returns the scalar type of `ret`, or a `Result` or `Option` of it.
+///
+/// ```ignore
+/// #[function("add(int32, int32) -> int32")]
+/// fn add(a: i32, b: i32) -> Result<i32> {
+///     a.checked_add(b).ok_or_else(|| ArrayError::Overflow(format!("{} + {}", a, b)))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let signature = parse_macro_input!(attr as LitStr);
+    let function = parse_macro_input!(item as ItemFn);
+    match generate(&signature, &function) {
+        Ok(registration) => quote! {
+            #function
+            #registration
+        }
+        .into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// A parsed `name(arg, ...) -> ret`.
+struct Signature {
+    name: String,
+    args: Vec<String>,
+    ret: String,
+}
+
+fn parse_signature(signature: &LitStr) -> Result<Signature> {
+    let error = || {
+        Error::new(
+            signature.span(),
+            "expect a signature like `name(arg, ...) -> ret`",
+        )
+    };
+    let value = signature.value();
+    let (name, rest) = value.split_once('(').ok_or_else(error)?;
+    let (args, ret) = rest.split_once(')').ok_or_else(error)?;
+    let ret = ret.trim().strip_prefix("->").ok_or_else(error)?;
+    let args = args
+        .split(',')
+        .map(|arg| arg.trim().to_string())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    Ok(Signature {
+        name: name.trim().to_string(),
+        args,
+        ret: ret.trim().to_string(),
+    })
+}
+
+/// The `DataType` variant and scalar type of a type name.
+fn data_type(name: &str, krate: &TokenStream2, span: Span) -> Result<(TokenStream2, TokenStream2)> {
+    Ok(match name {
+        "boolean" => (quote! { Boolean }, quote! { bool }),
+        "int16" => (quote! { SmallInt }, quote! { i16 }),
+        "int32" => (quote! { Integer }, quote! { i32 }),
+        "int64" => (quote! { BigInt }, quote! { i64 }),
+        "float32" => (quote! { Real }, quote! { f32 }),
+        "float64" => (quote! { Double }, quote! { f64 }),
+        "decimal" => (
+            quote! { Decimal { scale: 0, precision: 38 } },
+            quote! { #krate::Decimal },
+        ),
+        "date" => (quote! { Date }, quote! { #krate::Date }),
+        "time" => (quote! { Time }, quote! { #krate::Time }),
+        "timestamp" => (
+            quote! { Timestamp { timezone: None } },
+            quote! { #krate::Timestamp },
+        ),
+        "interval" => (quote! { Interval }, quote! { #krate::Interval }),
+        "varchar" => (quote! { Varchar }, quote! { String }),
+        "bytea" => (quote! { Bytea }, quote! { Vec<u8> }),
+        _ => return Err(Error::new(span, format!("unknown type `{}`", name))),
+    })
+}
+
+fn generate(signature: &LitStr, function: &ItemFn) -> Result<TokenStream2> {
+    let span = signature.span();
+    let Signature { name, args, ret } = parse_signature(signature)?;
+    let fn_name = &function.sig.ident;
+    if args.len() != function.sig.inputs.len() {
+        return Err(Error::new(
+            span,
+            format!(
+                "signature has {} arguments, but `{}` takes {}",
+                args.len(),
+                fn_name,
+                function.sig.inputs.len()
+            ),
+        ));
+    }
+
+    // Refer to the `array` crate as `crate` from within itself.
+    let krate = match std::env::var("CARGO_CRATE_NAME").as_deref() {
+        Ok("array") => quote! { crate },
+        _ => quote! { ::array },
+    };
+    let mut arg_data_types = Vec::with_capacity(args.len());
+    let mut arg_scalar_types = Vec::with_capacity(args.len());
+    for arg in &args {
+        let (data_type, scalar_type) = data_type(arg, &krate, span)?;
+        arg_data_types.push(data_type);
+        arg_scalar_types.push(scalar_type);
+    }
+    let (ret_data_type, ret_scalar_type) = data_type(&ret, &krate, span)?;
+    let arg_idents: Vec<_> = (0..args.len()).map(|i| format_ident!("arg{}", i)).collect();
+
+    let expression = match args.len() {
+        1 => quote! { UnaryExpression },
+        2 => quote! { BinaryExpression },
+        3 => quote! { TernaryExpression },
+        n => {
+            return Err(Error::new(
+                span,
+                format!("functions with {} arguments are not supported", n),
+            ))
+        }
+    };
+
+    Ok(quote! {
+        const _: () = {
+            #[#krate::__private::linkme::distributed_slice(#krate::FUNCTIONS)]
+            #[linkme(crate = #krate::__private::linkme)]
+            static FUNCTION: #krate::FunctionDescriptor = #krate::FunctionDescriptor {
+                name: #name,
+                args: &[#(#krate::DataType::#arg_data_types),*],
+                ret: #krate::DataType::#ret_data_type,
+                build: || {
+                    ::std::boxed::Box::new(
+                        #krate::#expression::<#(#arg_scalar_types,)* _, _>::new(#fn_name),
+                    )
+                },
+            };
+
+            // Check that the function returns the declared type.
+            #[allow(dead_code)]
+            fn check_return_type(
+                #(#arg_idents: <#arg_scalar_types as #krate::Scalar>::RefType<'_>),*
+            ) -> impl #krate::FunctionOutput<Scalar = #ret_scalar_type> {
+                #fn_name(#(#arg_idents),*)
+            }
+        };
+    })
+}
@@ -1,4 +1,6 @@
-use crate::{Scalar, ScalarRef};
+use std::ops::Range;
+
+use crate::{Bitmap, Result, Scalar, ScalarRef};
 
 /// [`Array`] is a container of the same type.
 /// Each item in the array can be null or not.
@@ -13,12 +15,53 @@ where
 
     fn len(&self) -> usize;
     fn get(&self, index: usize) -> Option<Self::RefItem<'_>>;
+    /// Get the value at `index` without checking whether it is null.
+    ///
+    /// The result is unspecified if the value is null, and it may panic.
+    fn value(&self, index: usize) -> Self::RefItem<'_>;
+    /// The validity of each value.
+    fn validity(&self) -> &Bitmap;
+    /// Get `len` rows starting at `offset`, without copying them.
+    ///
+    /// Panics if the rows are out of bounds.
+    fn slice(&self, offset: usize, len: usize) -> Self;
+
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    /// Number of null values.
+    fn null_count(&self) -> usize {
+        self.validity().null_count()
+    }
+    /// Whether the value at `index` is null.
+    ///
+    /// Panics if the index is out of bounds.
+    fn is_null(&self, index: usize) -> bool {
+        !self.validity().is_valid(index)
+    }
     fn iter(&self) -> ArrayIterator<'_, Self> {
         ArrayIterator::new(self)
     }
+
+    /// Create an empty builder for arrays of the same type as this one, including the scale of
+    /// decimals and the types of nested values.
+    fn new_builder(&self, capacity: usize) -> Self::Builder {
+        Self::Builder::with_capacity(capacity)
+    }
+
+    /// Build an array from nullable values.
+    fn from_slice(data: &[Option<Self::RefItem<'_>>]) -> Self {
+        let mut builder = Self::Builder::with_capacity(data.len());
+        builder.extend_from_iter(data.iter().copied());
+        builder.finish()
+    }
+
+    /// Build an array without nulls.
+    fn from_values(data: &[Self::RefItem<'_>]) -> Self {
+        let mut builder = Self::Builder::with_capacity(data.len());
+        builder.extend_from_iter(data.iter().map(|&value| Some(value)));
+        builder.finish()
+    }
 }
 
 /// Array Builder is to build certain Array
@@ -31,11 +74,72 @@ pub trait ArrayBuilder {
     /// Append a value to builder.
     fn push(&mut self, item: Option<<<Self as ArrayBuilder>::Array as Array>::RefItem<'_>>);
 
+    /// Append a value to builder, or fail without appending anything if its nested values
+    /// don't match the types of the builder, or it doesn't fit a decimal builder.
+    ///
+    /// Builders whose [`push`](Self::push) can panic override this to return the error.
+    fn try_push(
+        &mut self,
+        item: Option<<<Self as ArrayBuilder>::Array as Array>::RefItem<'_>>,
+    ) -> Result<()> {
+        self.push(item);
+        Ok(())
+    }
+
+    /// Check that the values of `array` can be appended to builder.
+    ///
+    /// Only builders of nested types can reject an array of their own type.
+    fn check(&self, _array: &Self::Array) -> Result<()> {
+        Ok(())
+    }
+
+    /// Number of values appended so far.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reserve capacity for at least `additional` more values.
+    fn reserve(&mut self, additional: usize);
+
+    /// Append `n` nulls to builder.
+    fn push_n_nulls(&mut self, n: usize) {
+        for _ in 0..n {
+            self.push(None);
+        }
+    }
+
+    /// Append all values of `iter` to builder.
+    fn extend_from_iter<'a>(
+        &mut self,
+        iter: impl IntoIterator<Item = Option<<Self::Array as Array>::RefItem<'a>>>,
+    ) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push(item);
+        }
+    }
+
+    /// Append all values of `array` to builder.
+    fn append_array(&mut self, array: &Self::Array) {
+        self.append_slice(array, 0..array.len());
+    }
+
+    /// Append the values of `array` in `range` to builder.
+    ///
+    /// Panics if the range is out of bounds.
+    fn append_slice(&mut self, array: &Self::Array, range: Range<usize>) {
+        assert!(range.end <= array.len(), "slice out of bounds");
+        self.reserve(range.len());
+        for index in range {
+            self.push(array.get(index));
+        }
+    }
+
     /// Finish building and return array
     fn finish(self) -> Self::Array;
-
-    // Extend array?
-    // fn extend(&mut self, array_iter: impl Array)
 }
 
 pub struct ArrayIterator<'a, A: Array> {
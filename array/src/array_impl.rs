@@ -1,17 +1,56 @@
-use bitvec::vec::BitVec;
+mod boolean;
+mod bytes;
+mod decimal;
+mod list;
+mod r#struct;
+
+use std::{ops::Range, sync::Arc};
+
+use bitvec::{slice::BitSlice, vec::BitVec};
 
 use crate::{
     array::{Array, ArrayBuilder},
-    PrimitiveType, Scalar, ScalarRef,
+    kernel::Kernel,
+    ArrayError, Bitmap, PrimitiveType, Result, Scalar, ScalarRef,
 };
 
+pub use boolean::*;
+pub use bytes::*;
+pub use decimal::*;
+pub use list::*;
+pub use r#struct::*;
+
+/// An array of fixed-size values.
+///
+/// The buffers are shared between clones and slices of an array, which only differ by the range
+/// of rows they cover.
+#[derive(Debug, Clone)]
 pub struct PrimitiveArray<T> {
     /// The actual data of this array
-    data: Vec<T>,
+    data: Arc<[T]>,
 
     /// The bitmap for this array
     /// If the bit is set, the corresponding value is not null.
-    bitmap: BitVec,
+    bitmap: Bitmap,
+
+    /// The first row of `data` covered by this array.
+    offset: usize,
+}
+
+impl<T> PrimitiveArray<T> {
+    /// Create an array without nulls, taking over the values.
+    pub fn from_vec(data: Vec<T>) -> Self {
+        Self {
+            bitmap: Bitmap::valid(data.len()),
+            offset: 0,
+            data: data.into(),
+        }
+    }
+
+    /// The values of this array, with an unspecified value for nulls.
+    fn values(&self) -> &[T] {
+        &self.data[self.offset..self.offset + self.bitmap.len()]
+    }
 }
 
 impl<T> Array for PrimitiveArray<T>
@@ -29,13 +68,82 @@ where
     }
 
     fn get(&self, index: usize) -> Option<Self::RefItem<'_>> {
-        match self.bitmap.get(index).as_deref() {
-            Some(&true) => self.data.get(index).copied(),
-            _ => None,
+        if index < self.len() && self.bitmap.is_valid(index) {
+            Some(self.data[self.offset + index])
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, index: usize) -> T {
+        self.values()[index]
+    }
+
+    fn validity(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn from_values(data: &[T]) -> Self {
+        Self::from_vec(data.to_vec())
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self {
+            data: self.data.clone(),
+            bitmap: self.bitmap.slice(offset, len),
+            offset: self.offset + offset,
         }
     }
 }
 
+impl<T> Kernel for PrimitiveArray<T>
+where
+    T: PrimitiveType,
+    T: for<'a> Scalar<RefType<'a> = T, ArrayTpye = Self>,
+    T: for<'a> ScalarRef<'a, ScalarType = T, ArrayType = Self>,
+{
+    fn gather(&self, indices: &[usize]) -> Self {
+        let values = self.values();
+        Self {
+            data: indices.iter().map(|&i| values[i]).collect(),
+            bitmap: self.bitmap.gather(indices),
+            offset: 0,
+        }
+    }
+
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            bitmap: self.bitmap.and(mask),
+            ..self.clone()
+        }
+    }
+
+    fn concat(arrays: &[&Self]) -> Result<Self> {
+        let mut data = Vec::with_capacity(arrays.iter().map(|array| array.len()).sum());
+        for array in arrays {
+            data.extend_from_slice(array.values());
+        }
+        Ok(Self {
+            data: data.into(),
+            bitmap: Bitmap::concat(arrays.iter().map(|array| &array.bitmap)),
+            offset: 0,
+        })
+    }
+}
+
+impl<T> FromIterator<Option<T>> for PrimitiveArray<T>
+where
+    T: PrimitiveType,
+    T: for<'a> Scalar<RefType<'a> = T, ArrayTpye = Self>,
+    T: for<'a> ScalarRef<'a, ScalarType = T, ArrayType = Self>,
+{
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        let mut builder = PrimitiveArrayBuilder::with_capacity(0);
+        builder.extend_from_iter(iter);
+        builder.finish()
+    }
+}
+
 pub struct PrimitiveArrayBuilder<T> {
     data: Vec<T>,
     bitmap: BitVec,
@@ -68,43 +176,52 @@ where
         }
     }
 
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        self.bitmap.reserve(additional);
+    }
+
+    fn push_n_nulls(&mut self, n: usize) {
+        self.data.resize(self.data.len() + n, T::default());
+        self.bitmap.resize(self.bitmap.len() + n, false);
+    }
+
+    fn append_slice(&mut self, array: &PrimitiveArray<T>, range: Range<usize>) {
+        self.data.extend_from_slice(&array.values()[range.clone()]);
+        array.bitmap.extend_to(&mut self.bitmap, range);
+    }
+
     fn finish(self) -> Self::Array {
         Self::Array {
-            data: self.data,
-            bitmap: self.bitmap,
+            data: self.data.into(),
+            bitmap: Bitmap::from_bits(self.bitmap),
+            offset: 0,
         }
     }
 }
 
-#[derive(Debug)]
+/// An array of UTF-8 strings.
+///
+/// It shares the layout of [`BytesArray`], and only guarantees that each value is valid UTF-8.
+#[derive(Debug, Clone)]
 pub struct StringArray {
-    /// The fallten data of strings.
-    data: Vec<u8>,
-    /// The offset of each string.
-    /// The length of offset is len + 1.
-    offset: Vec<usize>,
-    /// The null bitmap for this array, which indicates whether an element at
-    /// `i` is null
-    bitmap: BitVec,
+    bytes: BytesArray,
 }
 
 impl StringArray {
     pub fn get(&self, index: usize) -> Option<&str> {
-        if self.bitmap.get(index).as_deref() == Some(&true) {
-            let start = self.offset[index];
-            let end = self.offset[index + 1];
-            Some(std::str::from_utf8(&self.data[start..end]).unwrap())
-        } else {
-            None
-        }
+        self.bytes.get(index).map(|bytes| {
+            // SAFETY: `StringArray` can only be built from `&str` or from a validated `BytesArray`.
+            unsafe { std::str::from_utf8_unchecked(bytes) }
+        })
     }
 
     pub fn from_slice(data: &[Option<&str>]) -> Self {
-        let mut builder = <Self as Array>::Builder::with_capacity(data.len());
-        for val in data {
-            builder.push(*val);
-        }
-        builder.finish()
+        <Self as Array>::from_slice(data)
     }
 }
 
@@ -117,54 +234,134 @@ impl Array for StringArray {
         self.get(index)
     }
 
+    fn value(&self, index: usize) -> &str {
+        // SAFETY: `StringArray` can only be built from `&str` or from a validated `BytesArray`.
+        unsafe { std::str::from_utf8_unchecked(self.bytes.value(index)) }
+    }
+
+    fn validity(&self) -> &Bitmap {
+        self.bytes.validity()
+    }
+
     fn len(&self) -> usize {
-        self.bitmap.len()
+        self.bytes.len()
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self {
+            bytes: self.bytes.slice(offset, len),
+        }
+    }
+}
+
+impl Kernel for StringArray {
+    fn gather(&self, indices: &[usize]) -> Self {
+        Self {
+            bytes: self.bytes.gather(indices),
+        }
+    }
+
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            bytes: self.bytes.mask(mask),
+        }
+    }
+
+    fn concat(arrays: &[&Self]) -> Result<Self> {
+        let bytes: Vec<_> = arrays.iter().map(|array| &array.bytes).collect();
+        Ok(Self {
+            bytes: BytesArray::concat(&bytes)?,
+        })
+    }
+}
+
+/// Implement `FromIterator` over nullable references and nullable owned values.
+macro_rules! impl_from_iter {
+    ($( { $Array:ty, $Ref:ty, $Owned:ty } ),*) => {
+        $(
+            impl<'a> FromIterator<Option<$Ref>> for $Array {
+                fn from_iter<I: IntoIterator<Item = Option<$Ref>>>(iter: I) -> Self {
+                    let mut builder = <$Array as Array>::Builder::with_capacity(0);
+                    builder.extend_from_iter(iter);
+                    builder.finish()
+                }
+            }
+
+            impl FromIterator<Option<$Owned>> for $Array {
+                fn from_iter<I: IntoIterator<Item = Option<$Owned>>>(iter: I) -> Self {
+                    let iter = iter.into_iter();
+                    let mut builder = <$Array as Array>::Builder::with_capacity(iter.size_hint().0);
+                    for item in iter {
+                        builder.push(item.as_ref().map(Scalar::as_scalar_ref));
+                    }
+                    builder.finish()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_iter! {
+    { StringArray, &'a str, String },
+    { BytesArray, &'a [u8], Vec<u8> },
+    { ListArray, ListRef<'a>, ListValue },
+    { StructArray, StructRef<'a>, StructValue }
+}
+
+impl From<StringArray> for BytesArray {
+    fn from(array: StringArray) -> Self {
+        array.bytes
+    }
+}
+
+impl TryFrom<BytesArray> for StringArray {
+    type Error = ArrayError;
+
+    /// Reinterpret a [`BytesArray`] as strings, checking every value is valid UTF-8.
+    fn try_from(bytes: BytesArray) -> Result<Self, Self::Error> {
+        for value in bytes.iter().flatten() {
+            std::str::from_utf8(value)?;
+        }
+        Ok(Self { bytes })
     }
 }
 
 pub struct StringArrayBuilder {
-    data: Vec<u8>,
-    bitmap: BitVec,
-    offsets: Vec<usize>,
+    bytes: BytesArrayBuilder,
 }
 
 impl ArrayBuilder for StringArrayBuilder {
     type Array = StringArray;
 
     fn with_capacity(capacity: usize) -> Self {
-        let mut offsets = Vec::with_capacity(capacity + 1);
-        offsets.push(0);
-
         Self {
-            data: Vec::new(),
-            bitmap: BitVec::with_capacity(capacity),
-            offsets,
+            bytes: BytesArrayBuilder::with_capacity(capacity),
         }
     }
 
     fn push(&mut self, item: Option<&str>) {
-        match item {
-            Some(item) => {
-                let bytes = item.to_string().into_bytes();
+        self.bytes.push(item.map(str::as_bytes));
+    }
 
-                self.bitmap.push(true);
-                self.data.extend(bytes);
-                self.offsets.push(self.data.len());
-            }
-            None => {
-                self.bitmap.push(false);
-                self.offsets.push(self.data.len());
-            }
-        }
+    fn len(&self) -> usize {
+        self.bytes.len()
     }
 
-    fn finish(self) -> Self::Array {
-        assert_eq!(self.bitmap.len() + 1, self.offsets.len());
+    fn reserve(&mut self, additional: usize) {
+        self.bytes.reserve(additional);
+    }
+
+    fn push_n_nulls(&mut self, n: usize) {
+        self.bytes.push_n_nulls(n);
+    }
+
+    fn append_slice(&mut self, array: &StringArray, range: Range<usize>) {
+        self.bytes.append_slice(&array.bytes, range);
+    }
 
+    fn finish(self) -> Self::Array {
         StringArray {
-            data: self.data,
-            offset: self.offsets,
-            bitmap: self.bitmap,
+            bytes: self.bytes.finish(),
         }
     }
 }
@@ -189,4 +386,134 @@ mod tests {
         assert_eq!(iter.next(), Some(None));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn slice_test() {
+        let mut builder = <PrimitiveArray<i32> as Array>::Builder::with_capacity(4);
+        for value in [Some(1), None, Some(3), Some(4)] {
+            builder.push(value);
+        }
+        let array = builder.finish();
+        let slice = array.slice(1, 2);
+        assert!(Arc::ptr_eq(&slice.data, &array.data));
+        assert_eq!(slice.iter().collect::<Vec<_>>(), [None, Some(3)]);
+        assert_eq!(slice.get(2), None);
+        assert_eq!(slice.slice(1, 1).iter().collect::<Vec<_>>(), [Some(3)]);
+        assert_eq!(array.slice(4, 0).len(), 0);
+
+        let strings = StringArray::from_slice(&[Some("a"), Some("bc"), None, Some("d")]);
+        let slice = strings.slice(1, 3);
+        assert_eq!(
+            slice.iter().collect::<Vec<_>>(),
+            [Some("bc"), None, Some("d")]
+        );
+        assert_eq!(slice.get(3), None);
+    }
+
+    #[test]
+    fn builder_bulk_test() {
+        let mut builder = <PrimitiveArray<i32> as Array>::Builder::with_capacity(0);
+        builder.reserve(8);
+        builder.extend_from_iter([Some(1), None, Some(3)]);
+        builder.push_n_nulls(2);
+        assert_eq!(builder.len(), 5);
+        let array = builder.finish();
+
+        let mut builder = <PrimitiveArray<i32> as Array>::Builder::with_capacity(0);
+        builder.append_array(&array.slice(2, 3));
+        builder.append_slice(&array, 0..2);
+        let array = builder.finish();
+        assert_eq!(
+            array.iter().collect::<Vec<_>>(),
+            [Some(3), None, None, Some(1), None]
+        );
+
+        let strings = StringArray::from_slice(&[Some("a"), Some("bc"), None, Some("d")]);
+        let mut builder = StringArrayBuilder::with_capacity(0);
+        builder.push(Some("x"));
+        builder.append_slice(&strings.slice(1, 3), 0..2);
+        builder.push_n_nulls(1);
+        builder.append_array(&strings.slice(3, 1));
+        builder.extend_from_iter(strings.iter().take(1));
+        assert_eq!(builder.len(), 6);
+        assert_eq!(
+            builder.finish().iter().collect::<Vec<_>>(),
+            [Some("x"), Some("bc"), None, None, Some("d"), Some("a")]
+        );
+    }
+
+    #[test]
+    fn from_iter_test() {
+        let array = PrimitiveArray::from_vec(vec![1, 2, 3]);
+        assert!(array.bitmap.as_bitslice().is_none());
+        assert_eq!(
+            array.slice(1, 2).iter().collect::<Vec<_>>(),
+            [Some(2), Some(3)]
+        );
+        assert!(PrimitiveArray::from_values(&[1.5])
+            .bitmap
+            .as_bitslice()
+            .is_none());
+        assert!(PrimitiveArray::from_slice(&[Some(1), Some(2)])
+            .bitmap
+            .as_bitslice()
+            .is_none());
+
+        let array: PrimitiveArray<i64> = [Some(1), None].into_iter().collect();
+        assert_eq!(array.iter().collect::<Vec<_>>(), [Some(1), None]);
+        let concat = PrimitiveArray::concat(&[&PrimitiveArray::from_vec(vec![0]), &array]).unwrap();
+        assert_eq!(concat.iter().collect::<Vec<_>>(), [Some(0), Some(1), None]);
+
+        let strings: StringArray = [Some("a"), None].into_iter().collect();
+        let owned: StringArray = [None, Some("b".to_string())].into_iter().collect();
+        assert_eq!(strings.iter().collect::<Vec<_>>(), [Some("a"), None]);
+        assert_eq!(owned.iter().collect::<Vec<_>>(), [None, Some("b")]);
+        assert_eq!(
+            StringArray::from_values(&["x", "y"])
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some("x"), Some("y")]
+        );
+
+        let bytes: BytesArray = [Some(vec![1u8]), None].into_iter().collect();
+        assert_eq!(bytes.get(0), Some(&[1u8][..]));
+    }
+
+    #[test]
+    fn null_count_test() {
+        let array = PrimitiveArray::from_slice(&[Some(1), None, Some(3), None]);
+        assert_eq!(array.null_count(), 2);
+        assert!(array.is_null(1));
+        assert!(!array.is_null(2));
+        assert_eq!(array.value(2), 3);
+        assert_eq!(array.slice(2, 1).null_count(), 0);
+        assert!(array.slice(2, 1).validity().all_valid());
+
+        let strings = StringArray::from_slice(&[Some("a"), None]);
+        assert_eq!(strings.null_count(), 1);
+        assert_eq!(strings.value(0), "a");
+        assert!(StringArray::from_values(&["a"]).validity().all_valid());
+    }
+
+    #[test]
+    #[should_panic(expected = "slice out of bounds")]
+    fn slice_out_of_bounds_test() {
+        StringArray::from_slice(&[Some("a")]).slice(1, 1);
+    }
+
+    #[test]
+    fn string_from_bytes_test() {
+        let bytes = BytesArray::from_slice(&[Some(&b"abc"[..]), None, Some(b"")]);
+        let strings = StringArray::try_from(bytes).unwrap();
+        assert_eq!(
+            strings.iter().collect::<Vec<_>>(),
+            [Some("abc"), None, Some("")]
+        );
+
+        let bytes: BytesArray = strings.into();
+        assert_eq!(bytes.get(0), Some(&b"abc"[..]));
+
+        let invalid = BytesArray::from_slice(&[Some(&b"ok"[..]), Some(&[0xff, 0xfe])]);
+        assert!(StringArray::try_from(invalid).is_err());
+    }
 }
@@ -0,0 +1,249 @@
+use std::{ops::Range, sync::Arc};
+
+use bitvec::{slice::BitSlice, vec::BitVec};
+
+use crate::{
+    array::{Array, ArrayBuilder},
+    bitmap::{from_words, words},
+    kernel::Kernel,
+    Bitmap, Result,
+};
+
+/// An array of booleans, packed into a bit per value.
+///
+/// The buffers are shared between clones and slices of an array, which only differ by the range
+/// of rows they cover.
+#[derive(Debug, Clone)]
+pub struct BooleanArray {
+    /// The value of each row of the buffers. The value of a null row is unspecified.
+    values: Arc<BitVec>,
+    /// The validity of the rows covered by this array.
+    bitmap: Bitmap,
+    /// The first row of `values` covered by this array.
+    offset: usize,
+}
+
+impl BooleanArray {
+    /// Create an array without nulls, taking over the values.
+    pub fn from_bits(values: BitVec) -> Self {
+        Self {
+            bitmap: Bitmap::valid(values.len()),
+            values: Arc::new(values),
+            offset: 0,
+        }
+    }
+
+    /// The values of the rows covered by this array, including the unspecified values of null
+    /// rows.
+    pub fn values(&self) -> &BitSlice {
+        &self.values[self.offset..self.offset + self.len()]
+    }
+
+    /// The rows that are true, as a bit per row. Null counts as false.
+    pub(crate) fn true_bits(&self) -> BitVec {
+        if self.bitmap.all_valid() {
+            return self.values().to_bitvec();
+        }
+        let values = words(self.values()).zip(self.bitmap.words());
+        from_words(values.map(|(value, valid)| value & valid), self.len())
+    }
+
+    /// Apply `f` to the values of this array, a word at a time, keeping the nulls.
+    pub(crate) fn map_words(&self, f: impl Fn(usize) -> usize) -> Self {
+        Self {
+            values: Arc::new(from_words(words(self.values()).map(f), self.len())),
+            bitmap: self.bitmap.clone(),
+            offset: 0,
+        }
+    }
+
+    /// Combine this array with `other`, which must have the same length, a word at a time.
+    ///
+    /// `f` takes the words of the values and validity of this array and of `other`, in that
+    /// order, and returns the words of the values and validity of the result.
+    pub(crate) fn zip_words(
+        &self,
+        other: &Self,
+        f: impl Fn(usize, usize, usize, usize) -> (usize, usize),
+    ) -> Self {
+        assert_eq!(self.len(), other.len(), "length mismatch");
+        let (values, validity): (Vec<_>, Vec<_>) = words(self.values())
+            .zip(self.bitmap.words())
+            .zip(words(other.values()).zip(other.bitmap.words()))
+            .map(|((left, left_valid), (right, right_valid))| {
+                f(left, left_valid, right, right_valid)
+            })
+            .unzip();
+        Self {
+            values: Arc::new(from_words(values, self.len())),
+            bitmap: Bitmap::from_bits(from_words(validity, self.len())),
+            offset: 0,
+        }
+    }
+}
+
+impl Array for BooleanArray {
+    type RefItem<'a> = bool;
+    type OwnedItem = bool;
+    type Builder = BooleanArrayBuilder;
+
+    fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len() && self.bitmap.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, index: usize) -> bool {
+        self.values[self.offset + index]
+    }
+
+    fn validity(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self {
+            bitmap: self.bitmap.slice(offset, len),
+            offset: self.offset + offset,
+            ..self.clone()
+        }
+    }
+}
+
+impl Kernel for BooleanArray {
+    fn gather(&self, indices: &[usize]) -> Self {
+        Self {
+            values: Arc::new(indices.iter().map(|&i| self.value(i)).collect()),
+            bitmap: self.bitmap.gather(indices),
+            offset: 0,
+        }
+    }
+
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            bitmap: self.bitmap.and(mask),
+            ..self.clone()
+        }
+    }
+
+    fn concat(arrays: &[&Self]) -> Result<Self> {
+        let mut values = BitVec::with_capacity(arrays.iter().map(|array| array.len()).sum());
+        for array in arrays {
+            values.extend_from_bitslice(array.values());
+        }
+        Ok(Self {
+            values: Arc::new(values),
+            bitmap: Bitmap::concat(arrays.iter().map(|array| &array.bitmap)),
+            offset: 0,
+        })
+    }
+}
+
+impl FromIterator<Option<bool>> for BooleanArray {
+    fn from_iter<I: IntoIterator<Item = Option<bool>>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut builder = BooleanArrayBuilder::with_capacity(iter.size_hint().0);
+        for value in iter {
+            builder.push(value);
+        }
+        builder.finish()
+    }
+}
+
+pub struct BooleanArrayBuilder {
+    values: BitVec,
+    bitmap: BitVec,
+}
+
+impl ArrayBuilder for BooleanArrayBuilder {
+    type Array = BooleanArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: BitVec::with_capacity(capacity),
+            bitmap: BitVec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, value: Option<bool>) {
+        self.values.push(value.unwrap_or_default());
+        self.bitmap.push(value.is_some());
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.values.reserve(additional);
+        self.bitmap.reserve(additional);
+    }
+
+    fn push_n_nulls(&mut self, n: usize) {
+        self.values.resize(self.values.len() + n, false);
+        self.bitmap.resize(self.bitmap.len() + n, false);
+    }
+
+    fn append_slice(&mut self, array: &BooleanArray, range: Range<usize>) {
+        assert!(range.end <= array.len(), "slice out of bounds");
+        self.values
+            .extend_from_bitslice(&array.values()[range.clone()]);
+        array.bitmap.extend_to(&mut self.bitmap, range);
+    }
+
+    fn finish(self) -> BooleanArray {
+        BooleanArray {
+            values: Arc::new(self.values),
+            bitmap: Bitmap::from_bits(self.bitmap),
+            offset: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boolean_array_test() {
+        let values: Vec<_> = (0..100)
+            .map(|i| (i % 7 != 0).then_some(i % 3 == 0))
+            .collect();
+        let array: BooleanArray = values.iter().copied().collect();
+        assert_eq!(array.len(), 100);
+        assert_eq!(array.null_count(), 15);
+        assert_eq!(array.iter().collect::<Vec<_>>(), values);
+
+        let slice = array.slice(3, 70);
+        assert_eq!(slice.iter().collect::<Vec<_>>(), values[3..73]);
+        let true_bits = slice.true_bits();
+        assert_eq!(
+            true_bits.iter_ones().collect::<Vec<_>>(),
+            (0..70)
+                .filter(|&i| values[i + 3] == Some(true))
+                .collect::<Vec<_>>()
+        );
+
+        let gathered = slice.gather(&[4, 0]);
+        assert_eq!(gathered.iter().collect::<Vec<_>>(), [None, Some(true)]);
+        let concat = BooleanArray::concat(&[&slice.slice(0, 2), &array.slice(0, 1)]).unwrap();
+        assert_eq!(
+            concat.iter().collect::<Vec<_>>(),
+            [Some(true), Some(false), None]
+        );
+
+        let mut builder = BooleanArrayBuilder::with_capacity(0);
+        builder.append_slice(&slice, 1..4);
+        builder.push_n_nulls(1);
+        assert_eq!(
+            builder.finish().iter().collect::<Vec<_>>(),
+            [Some(false), Some(false), Some(true), None]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use std::{ops::Range, sync::Arc};
+
+use bitvec::{slice::BitSlice, vec::BitVec};
+
+use crate::{
+    array::{Array, ArrayBuilder},
+    kernel::Kernel,
+    Bitmap, Result,
+};
+
+/// An array of arbitrary binary values.
+///
+/// The buffers are shared between clones and slices of an array, which only differ by the range
+/// of rows they cover.
+#[derive(Debug, Clone)]
+pub struct BytesArray {
+    /// The fallten data of all values.
+    data: Arc<[u8]>,
+    /// The offset of each value into `data`.
+    /// The length of offsets is the number of rows of the buffers + 1.
+    offsets: Arc<[usize]>,
+    /// The validity of the rows covered by this array.
+    bitmap: Bitmap,
+    /// The first row of the buffers covered by this array.
+    offset: usize,
+}
+
+impl BytesArray {
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        if index < self.len() && self.bitmap.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    /// Build an array from nullable values of any byte container, such as `Vec<u8>`.
+    pub fn from_slice<T: AsRef<[u8]>>(data: &[Option<T>]) -> Self {
+        let data: Vec<_> = data
+            .iter()
+            .map(|val| val.as_ref().map(AsRef::as_ref))
+            .collect();
+        <Self as Array>::from_slice(&data)
+    }
+
+    /// The offsets of the rows covered by this array, one more than the number of rows.
+    fn offsets(&self) -> &[usize] {
+        &self.offsets[self.offset..=self.offset + self.len()]
+    }
+}
+
+impl Array for BytesArray {
+    type RefItem<'a> = &'a [u8];
+    type OwnedItem = Vec<u8>;
+    type Builder = BytesArrayBuilder;
+
+    fn get<'b>(&'b self, index: usize) -> Option<Self::RefItem<'b>> {
+        self.get(index)
+    }
+
+    /// The `index`-th value, which is empty if it is null.
+    fn value(&self, index: usize) -> &[u8] {
+        let start = self.offsets[self.offset + index];
+        let end = self.offsets[self.offset + index + 1];
+        &self.data[start..end]
+    }
+
+    fn validity(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self {
+            bitmap: self.bitmap.slice(offset, len),
+            offset: self.offset + offset,
+            ..self.clone()
+        }
+    }
+}
+
+impl Kernel for BytesArray {
+    fn gather(&self, indices: &[usize]) -> Self {
+        let mut data = Vec::new();
+        let mut offsets = Vec::with_capacity(indices.len() + 1);
+        offsets.push(0);
+        for &i in indices {
+            data.extend_from_slice(self.value(i));
+            offsets.push(data.len());
+        }
+        Self {
+            data: data.into(),
+            offsets: offsets.into(),
+            bitmap: self.bitmap.gather(indices),
+            offset: 0,
+        }
+    }
+
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            bitmap: self.bitmap.and(mask),
+            ..self.clone()
+        }
+    }
+
+    fn concat(arrays: &[&Self]) -> Result<Self> {
+        let len: usize = arrays.iter().map(|array| array.len()).sum();
+        let mut data = Vec::new();
+        let mut offsets = Vec::with_capacity(len + 1);
+        offsets.push(0);
+        for array in arrays {
+            let offsets_of_array = array.offsets();
+            let (start, end) = (offsets_of_array[0], offsets_of_array[array.len()]);
+            let base = data.len();
+            data.extend_from_slice(&array.data[start..end]);
+            offsets.extend(offsets_of_array[1..].iter().map(|o| o - start + base));
+        }
+        Ok(Self {
+            data: data.into(),
+            offsets: offsets.into(),
+            bitmap: Bitmap::concat(arrays.iter().map(|array| &array.bitmap)),
+            offset: 0,
+        })
+    }
+}
+
+pub struct BytesArrayBuilder {
+    data: Vec<u8>,
+    bitmap: BitVec,
+    offsets: Vec<usize>,
+}
+
+impl ArrayBuilder for BytesArrayBuilder {
+    type Array = BytesArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+
+        Self {
+            data: Vec::new(),
+            bitmap: BitVec::with_capacity(capacity),
+            offsets,
+        }
+    }
+
+    fn push(&mut self, item: Option<&[u8]>) {
+        match item {
+            Some(item) => {
+                self.bitmap.push(true);
+                self.data.extend_from_slice(item);
+                self.offsets.push(self.data.len());
+            }
+            None => {
+                self.bitmap.push(false);
+                self.offsets.push(self.data.len());
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.bitmap.reserve(additional);
+        self.offsets.reserve(additional);
+    }
+
+    fn push_n_nulls(&mut self, n: usize) {
+        self.bitmap.resize(self.bitmap.len() + n, false);
+        self.offsets.resize(self.offsets.len() + n, self.data.len());
+    }
+
+    fn append_slice(&mut self, array: &BytesArray, range: Range<usize>) {
+        assert!(range.end <= array.len(), "slice out of bounds");
+        let offsets = &array.offsets()[range.start..=range.end];
+        let (start, end) = (offsets[0], offsets[offsets.len() - 1]);
+        let base = self.data.len();
+        self.data.extend_from_slice(&array.data[start..end]);
+        self.offsets
+            .extend(offsets[1..].iter().map(|offset| offset - start + base));
+        array.bitmap.extend_to(&mut self.bitmap, range);
+    }
+
+    fn finish(self) -> Self::Array {
+        assert_eq!(self.bitmap.len() + 1, self.offsets.len());
+
+        BytesArray {
+            offset: 0,
+            data: self.data.into(),
+            offsets: self.offsets.into(),
+            bitmap: Bitmap::from_bits(self.bitmap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_array_test() {
+        let array = BytesArray::from_slice(&[Some(vec![0u8, 159, 146, 150]), None, Some(vec![])]);
+
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.get(0), Some(&[0u8, 159, 146, 150][..]));
+        assert_eq!(array.get(1), None);
+        assert_eq!(array.get(2), Some(&[][..]));
+    }
+}
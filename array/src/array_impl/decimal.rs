@@ -0,0 +1,339 @@
+use std::{ops::Range, sync::Arc};
+
+use bitvec::{slice::BitSlice, vec::BitVec};
+
+use crate::{
+    array::{Array, ArrayBuilder},
+    decimal::{MAX_PRECISION, MAX_SCALE},
+    kernel::Kernel,
+    ArrayError, Bitmap, Decimal, Result,
+};
+
+/// An array of decimals, stored as mantissas sharing a single scale.
+///
+/// The buffers are shared between clones and slices of an array, which only differ by the range
+/// of rows they cover.
+#[derive(Debug, Clone)]
+pub struct DecimalArray {
+    /// The mantissa of each row of the buffers, at `scale`.
+    data: Arc<[i128]>,
+    /// The validity of the rows covered by this array.
+    bitmap: Bitmap,
+    /// The first row of `data` covered by this array.
+    offset: usize,
+    scale: u8,
+}
+
+impl DecimalArray {
+    /// The number of digits after the decimal point of every value.
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// The mantissas of this array, with an unspecified value for nulls.
+    fn mantissas(&self) -> &[i128] {
+        &self.data[self.offset..self.offset + self.len()]
+    }
+}
+
+impl Array for DecimalArray {
+    type RefItem<'a> = Decimal;
+    type OwnedItem = Decimal;
+    type Builder = DecimalArrayBuilder;
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn get(&self, index: usize) -> Option<Decimal> {
+        if index < self.len() && self.bitmap.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, index: usize) -> Decimal {
+        Decimal::from_parts(self.mantissas()[index], self.scale)
+    }
+
+    fn validity(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self {
+            bitmap: self.bitmap.slice(offset, len),
+            offset: self.offset + offset,
+            ..self.clone()
+        }
+    }
+
+    fn new_builder(&self, capacity: usize) -> DecimalArrayBuilder {
+        DecimalArrayBuilder::with_scale(capacity, self.scale)
+    }
+}
+
+impl Kernel for DecimalArray {
+    fn gather(&self, indices: &[usize]) -> Self {
+        let mantissas = self.mantissas();
+        Self {
+            data: indices.iter().map(|&i| mantissas[i]).collect(),
+            bitmap: self.bitmap.gather(indices),
+            offset: 0,
+            scale: self.scale,
+        }
+    }
+
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            bitmap: self.bitmap.and(mask),
+            ..self.clone()
+        }
+    }
+}
+
+impl FromIterator<Option<Decimal>> for DecimalArray {
+    fn from_iter<I: IntoIterator<Item = Option<Decimal>>>(iter: I) -> Self {
+        let mut builder = DecimalArrayBuilder::with_capacity(0);
+        builder.extend_from_iter(iter);
+        builder.finish()
+    }
+}
+
+/// A builder of [`DecimalArray`]s.
+///
+/// A builder created with [`with_scale`](Self::with_scale) rounds every value to that scale.
+/// Otherwise, the scale of the array is the largest scale of the values pushed.
+pub struct DecimalArrayBuilder {
+    data: Vec<i128>,
+    bitmap: BitVec,
+    scale: u8,
+    /// Whether `scale` was given up front, rather than widened to fit the values.
+    fixed: bool,
+    /// The maximum number of digits of a value at `scale`, if limited.
+    precision: Option<u8>,
+}
+
+impl DecimalArrayBuilder {
+    /// Create a builder of decimals with `scale` digits after the point.
+    ///
+    /// Panics if `scale` is larger than [`MAX_SCALE`].
+    pub fn with_scale(capacity: usize, scale: u8) -> Self {
+        assert!(scale <= MAX_SCALE, "decimal scale {} out of range", scale);
+        Self {
+            scale,
+            fixed: true,
+            ..Self::with_capacity(capacity)
+        }
+    }
+
+    /// Create a builder of decimals with at most `precision` digits, `scale` of them after the
+    /// point, as in `DECIMAL(precision, scale)`.
+    ///
+    /// Panics if `precision` is zero or larger than [`MAX_PRECISION`], or if `scale` is larger
+    /// than [`MAX_SCALE`].
+    pub fn with_precision(capacity: usize, precision: u8, scale: u8) -> Self {
+        assert!(
+            (1..=MAX_PRECISION).contains(&precision),
+            "decimal precision {} out of range",
+            precision
+        );
+        Self {
+            precision: Some(precision),
+            ..Self::with_scale(capacity, scale)
+        }
+    }
+
+    /// The scale of the array once `value` is pushed.
+    fn scale_for(&self, value: Decimal) -> u8 {
+        match self.fixed {
+            true => self.scale,
+            false => self.scale.max(value.scale()),
+        }
+    }
+
+    /// The mantissa of `value` at `scale`, if it fits the builder.
+    fn fit(&self, value: Decimal, scale: u8) -> Result<i128> {
+        value
+            .rescale(scale)
+            .filter(|value| self.precision.is_none_or(|p| value.precision() <= p))
+            .map(|value| value.mantissa())
+            .ok_or_else(|| {
+                ArrayError::Overflow(format!(
+                    "decimal {} out of range for scale {}",
+                    value, scale
+                ))
+            })
+    }
+
+    /// The mantissas of the values pushed so far, widened to `scale`.
+    fn widened(&self, scale: u8) -> Result<Vec<i128>> {
+        let factor = 10i128.pow((scale - self.scale) as u32);
+        self.data
+            .iter()
+            .map(|mantissa| mantissa.checked_mul(factor))
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                ArrayError::Overflow(format!("decimals out of range for scale {}", scale))
+            })
+    }
+}
+
+impl ArrayBuilder for DecimalArrayBuilder {
+    type Array = DecimalArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            bitmap: BitVec::with_capacity(capacity),
+            scale: 0,
+            fixed: false,
+            precision: None,
+        }
+    }
+
+    /// Push a value, converted to the scale of the array.
+    ///
+    /// Panics if the value doesn't fit at that scale. Use [`try_push`](ArrayBuilder::try_push)
+    /// to get an error instead.
+    fn push(&mut self, value: Option<Decimal>) {
+        if let Err(err) = self.try_push(value) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Push a value, converted to the scale of the array, or fail with
+    /// [`ArrayError::Overflow`] without appending anything if it doesn't fit.
+    fn try_push(&mut self, value: Option<Decimal>) -> Result<()> {
+        let Some(value) = value else {
+            self.data.push(0);
+            self.bitmap.push(false);
+            return Ok(());
+        };
+        let scale = self.scale_for(value);
+        let mantissa = self.fit(value, scale)?;
+        if scale != self.scale {
+            self.data = self.widened(scale)?;
+            self.scale = scale;
+        }
+        self.data.push(mantissa);
+        self.bitmap.push(true);
+        Ok(())
+    }
+
+    fn check(&self, array: &DecimalArray) -> Result<()> {
+        let scale = match self.fixed {
+            true => self.scale,
+            false => self.scale.max(array.scale),
+        };
+        if scale != self.scale {
+            self.widened(scale)?;
+        }
+        if scale == array.scale && self.precision.is_none() {
+            return Ok(());
+        }
+        for value in array.iter().flatten() {
+            self.fit(value, scale)?;
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        self.bitmap.reserve(additional);
+    }
+
+    fn push_n_nulls(&mut self, n: usize) {
+        self.data.resize(self.data.len() + n, 0);
+        self.bitmap.resize(self.bitmap.len() + n, false);
+    }
+
+    fn append_slice(&mut self, array: &DecimalArray, range: Range<usize>) {
+        if array.scale != self.scale || self.precision.is_some() {
+            for index in range {
+                self.push(array.get(index));
+            }
+            return;
+        }
+        self.data
+            .extend_from_slice(&array.mantissas()[range.clone()]);
+        array.bitmap.extend_to(&mut self.bitmap, range);
+    }
+
+    fn finish(self) -> DecimalArray {
+        DecimalArray {
+            data: self.data.into(),
+            bitmap: Bitmap::from_bits(self.bitmap),
+            offset: 0,
+            scale: self.scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn decimal_array_test() {
+        let array = DecimalArray::from_slice(&[Some(dec("1.5")), None, Some(dec("-0.25"))]);
+        assert_eq!(array.scale(), 2);
+        assert_eq!(array.value(0).scale(), 2);
+        assert_eq!(
+            array.get(0).map(|d| d.to_string()),
+            Some("1.50".to_string())
+        );
+        assert_eq!(array.get(1), None);
+
+        let mut builder = DecimalArrayBuilder::with_scale(2, 1);
+        builder.push(Some(dec("1.25")));
+        builder.append_slice(&array, 1..3);
+        let rounded = builder.finish();
+        assert_eq!(rounded.scale(), 1);
+        assert_eq!(
+            rounded
+                .iter()
+                .map(|d| d.map(|d| d.to_string()))
+                .collect::<Vec<_>>(),
+            [Some("1.3".to_string()), None, Some("-0.3".to_string())]
+        );
+
+        let concat = DecimalArray::concat(&[&rounded, &array.slice(2, 1)]).unwrap();
+        assert_eq!(concat.scale(), 2);
+        assert_eq!(concat.get(3), Some(dec("-0.25")));
+        assert_eq!(concat.get(0).map(|d| d.mantissa()), Some(130));
+    }
+
+    #[test]
+    fn decimal_overflow_test() {
+        let mut builder = DecimalArrayBuilder::with_capacity(2);
+        builder.push(Some(Decimal::new(i128::MAX, 0).unwrap()));
+        assert!(matches!(
+            builder.try_push(Some(dec("0.1"))),
+            Err(ArrayError::Overflow(_))
+        ));
+        let mut small = DecimalArrayBuilder::with_capacity(1);
+        small.push(Some(dec("0.1")));
+        assert!(builder.check(&small.finish()).is_err());
+        let array = builder.finish();
+        assert_eq!(array.len(), 1);
+        assert_eq!(array.scale(), 0);
+
+        let mut builder = DecimalArrayBuilder::with_precision(2, 3, 1);
+        builder.push(Some(dec("12.34")));
+        assert!(builder.try_push(Some(dec("123.4"))).is_err());
+        assert!(builder.try_push(Some(dec("99.96"))).is_err());
+        assert!(builder.check(&array).is_err());
+        let array = builder.finish();
+        assert_eq!(array.iter().collect::<Vec<_>>(), [Some(dec("12.3"))]);
+    }
+}
@@ -0,0 +1,424 @@
+use std::{fmt, sync::Arc};
+
+use bitvec::{slice::BitSlice, vec::BitVec};
+
+use crate::{
+    array::{Array, ArrayBuilder},
+    dispatch::{ArrayBuilderImpl, ArrayImpl, ScalarRefImpl},
+    kernel::{self, Kernel},
+    Bitmap, DataType, Result, Scalar, ScalarRef,
+};
+
+/// An array of variable-length lists.
+///
+/// The elements of all lists are flattened into a single child array, and list `i`
+/// spans `child[offsets[i]..offsets[i + 1]]`.
+///
+/// The buffers are shared between clones and slices of an array, which only differ by the range
+/// of rows they cover.
+#[derive(Debug, Clone)]
+pub struct ListArray {
+    /// The offset of each list into `child`.
+    /// The length of offsets is the number of rows of the buffers + 1.
+    offsets: Arc<[usize]>,
+    /// The validity of the rows covered by this array.
+    bitmap: Bitmap,
+    /// The flattened elements. `None` if the element type is unknown, which only happens
+    /// when the array was built without an element type and no list was pushed at all.
+    child: Option<Arc<ArrayImpl>>,
+    /// The first row of the buffers covered by this array.
+    offset: usize,
+}
+
+impl ListArray {
+    /// The flattened elements of all lists.
+    ///
+    /// A slice of an array shares the elements of the whole array.
+    pub fn child(&self) -> Option<&ArrayImpl> {
+        self.child.as_deref()
+    }
+}
+
+impl Array for ListArray {
+    type RefItem<'a> = ListRef<'a>;
+    type OwnedItem = ListValue;
+    type Builder = ListArrayBuilder;
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn get(&self, index: usize) -> Option<Self::RefItem<'_>> {
+        if index < self.len() && self.bitmap.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, index: usize) -> Self::RefItem<'_> {
+        ListRef {
+            array: self.child.as_deref().expect("no list in the array"),
+            start: self.offsets[self.offset + index],
+            end: self.offsets[self.offset + index + 1],
+        }
+    }
+
+    fn validity(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self {
+            bitmap: self.bitmap.slice(offset, len),
+            offset: self.offset + offset,
+            ..self.clone()
+        }
+    }
+
+    fn new_builder(&self, capacity: usize) -> ListArrayBuilder {
+        ListArrayBuilder {
+            child: self
+                .child
+                .as_ref()
+                .map(|child| Box::new(child.new_builder(0))),
+            ..ListArrayBuilder::with_capacity(capacity)
+        }
+    }
+}
+
+impl Kernel for ListArray {
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            bitmap: self.bitmap.and(mask),
+            ..self.clone()
+        }
+    }
+}
+
+/// A builder of [`ListArray`]s.
+///
+/// A builder created with [`with_element`](Self::with_element) only accepts lists of that
+/// element type. Otherwise, the element type is the one of the first list pushed.
+pub struct ListArrayBuilder {
+    offsets: Vec<usize>,
+    bitmap: BitVec,
+    /// The builder of the flattened elements, `None` until the element type is known.
+    child: Option<Box<ArrayBuilderImpl>>,
+}
+
+impl ListArrayBuilder {
+    /// Create a builder of lists of `element`s.
+    ///
+    /// Fails if `element` has no builder, such as a decimal with a scale out of range.
+    pub fn with_element(capacity: usize, element: &DataType) -> Result<Self> {
+        Ok(Self {
+            child: Some(Box::new(ArrayBuilderImpl::with_capacity(element, 0)?)),
+            ..Self::with_capacity(capacity)
+        })
+    }
+}
+
+impl ArrayBuilder for ListArrayBuilder {
+    type Array = ListArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+
+        Self {
+            offsets,
+            bitmap: BitVec::with_capacity(capacity),
+            child: None,
+        }
+    }
+
+    /// Append a list to builder.
+    ///
+    /// Panics if its elements don't match the element type of the builder. Use
+    /// [`try_push`](ArrayBuilder::try_push) to get an error instead.
+    fn push(&mut self, item: Option<ListRef<'_>>) {
+        if let Err(err) = self.try_push(item) {
+            panic!("{}", err);
+        }
+    }
+
+    fn try_push(&mut self, item: Option<ListRef<'_>>) -> Result<()> {
+        let end = *self.offsets.last().unwrap();
+        match item {
+            Some(list) => {
+                let child = self
+                    .child
+                    .get_or_insert_with(|| Box::new(list.array.new_builder(list.len())));
+                child.append_array(&list.array.slice(list.start, list.len()))?;
+                self.bitmap.push(true);
+                self.offsets.push(end + list.len());
+            }
+            None => {
+                self.bitmap.push(false);
+                self.offsets.push(end);
+            }
+        }
+        Ok(())
+    }
+
+    fn check(&self, array: &ListArray) -> Result<()> {
+        match (&self.child, &array.child) {
+            (Some(builder), Some(child)) => builder.check(child),
+            _ => Ok(()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.bitmap.reserve(additional);
+        self.offsets.reserve(additional);
+    }
+
+    fn finish(self) -> Self::Array {
+        assert_eq!(self.bitmap.len() + 1, self.offsets.len());
+
+        ListArray {
+            offset: 0,
+            offsets: self.offsets.into(),
+            bitmap: Bitmap::from_bits(self.bitmap),
+            child: self.child.map(|child| Arc::new(child.finish())),
+        }
+    }
+}
+
+/// A reference to a list, either an element of a [`ListArray`] or a [`ListValue`].
+#[derive(Clone, Copy)]
+pub struct ListRef<'a> {
+    array: &'a ArrayImpl,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> ListRef<'a> {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Get the `index`-th element of this list, or `None` if it is null or out of bounds.
+    pub fn get(&self, index: usize) -> Option<ScalarRefImpl<'a>> {
+        if index < self.len() {
+            self.array.get(self.start + index)
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<ScalarRefImpl<'a>>> + 'a {
+        let array = self.array;
+        (self.start..self.end).map(move |i| array.get(i))
+    }
+}
+
+impl fmt::Debug for ListRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for ListRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+/// An owned list, holding its elements in an [`ArrayImpl`].
+#[derive(Clone)]
+pub struct ListValue {
+    values: Box<ArrayImpl>,
+}
+
+impl ListValue {
+    pub fn new(values: ArrayImpl) -> Self {
+        Self {
+            values: Box::new(values),
+        }
+    }
+
+    pub fn values(&self) -> &ArrayImpl {
+        &self.values
+    }
+}
+
+impl fmt::Debug for ListValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_scalar_ref().fmt(f)
+    }
+}
+
+impl PartialEq for ListValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_scalar_ref() == other.as_scalar_ref()
+    }
+}
+
+impl Scalar for ListValue {
+    type RefType<'a> = ListRef<'a>;
+
+    type ArrayTpye = ListArray;
+
+    fn as_scalar_ref<'a>(&'a self) -> Self::RefType<'a> {
+        ListRef {
+            array: &self.values,
+            start: 0,
+            end: self.values.len(),
+        }
+    }
+    fn upcast_to<'a, 'b: 'a>(ref_item: Self::RefType<'b>) -> Self::RefType<'a> {
+        ref_item
+    }
+}
+
+impl<'a> ScalarRef<'a> for ListRef<'a> {
+    type ScalarType = ListValue;
+
+    type ArrayType = ListArray;
+
+    fn to_scalar_owned(&self) -> Self::ScalarType {
+        let indices: Vec<_> = (self.start..self.end).collect();
+        ListValue::new(kernel::gather(self.array, &indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{array_impl::StringArray, prelude::Int32ArrayBuilder};
+
+    fn int32_list(values: &[Option<i32>]) -> ListValue {
+        let mut builder = Int32ArrayBuilder::with_capacity(values.len());
+        for value in values {
+            builder.push(*value);
+        }
+        ListValue::new(builder.finish().into())
+    }
+
+    #[test]
+    fn list_array_test() {
+        let lists = [
+            Some(int32_list(&[Some(1), Some(2)])),
+            None,
+            Some(int32_list(&[])),
+            Some(int32_list(&[None, Some(3)])),
+        ];
+        let mut builder = ListArrayBuilder::with_capacity(lists.len());
+        for list in &lists {
+            builder.push(list.as_ref().map(|list| list.as_scalar_ref()));
+        }
+        let array = builder.finish();
+
+        assert_eq!(array.len(), 4);
+        assert_eq!(array.child().unwrap().len(), 4);
+        for (item, expected) in array.iter().zip(&lists) {
+            assert_eq!(
+                item.map(|list| list.to_scalar_owned()).as_ref(),
+                expected.as_ref()
+            );
+        }
+
+        let list = array.get(3).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.get(0), None);
+        assert_eq!(list.get(1), Some(ScalarRefImpl::Int32(3)));
+        assert_eq!(list.get(2), None);
+        assert!(array.get(2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_slice_test() {
+        let lists = [
+            Some(int32_list(&[Some(1), Some(2)])),
+            None,
+            Some(int32_list(&[Some(3)])),
+        ];
+        let mut builder = ListArrayBuilder::with_capacity(lists.len());
+        for list in &lists {
+            builder.push(list.as_ref().map(|list| list.as_scalar_ref()));
+        }
+        let array = builder.finish();
+
+        let slice = array.slice(1, 2);
+        assert_eq!(slice.len(), 2);
+        assert_eq!(slice.get(0), None);
+        assert_eq!(
+            slice.get(1).map(|list| list.to_scalar_owned()),
+            lists[2].clone()
+        );
+        assert_eq!(slice.get(2), None);
+    }
+
+    #[test]
+    fn nested_list_test() {
+        let strings = StringArray::from_slice(&[Some("a"), None, Some("bc")]);
+        let inner = ListValue::new(strings.into());
+
+        let mut builder = ListArrayBuilder::with_capacity(1);
+        builder.push(Some(inner.as_scalar_ref()));
+        let outer = ListValue::new(builder.finish().into());
+
+        let mut builder = ListArrayBuilder::with_capacity(2);
+        builder.push(Some(outer.as_scalar_ref()));
+        builder.push(Some(outer.as_scalar_ref()));
+        let array: ArrayImpl = builder.finish().into();
+
+        match array.get(1) {
+            Some(ScalarRefImpl::List(list)) => {
+                assert_eq!(
+                    list.get(0),
+                    Some(ScalarRefImpl::List(inner.as_scalar_ref()))
+                );
+            }
+            _ => panic!("expect a list"),
+        }
+    }
+
+    #[test]
+    fn all_null_list_test() {
+        let mut builder = ListArrayBuilder::with_capacity(2);
+        builder.push(None);
+        builder.push(None);
+        let array = builder.finish();
+
+        assert!(array.child().is_none());
+        assert_eq!(array.iter().collect::<Vec<_>>(), vec![None, None]);
+
+        let mut builder = ListArrayBuilder::with_element(2, &DataType::Integer).unwrap();
+        builder.push_n_nulls(2);
+        let array = builder.finish();
+        assert_eq!(array.child().unwrap().type_name(), "Int32Array");
+        assert_eq!(array.new_builder(0).finish().child().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn list_type_mismatch_test() {
+        let strings = ListValue::new(StringArray::from_slice(&[Some("a")]).into());
+        let mut builder = ListArrayBuilder::with_element(2, &DataType::Integer).unwrap();
+        builder.push(Some(int32_list(&[Some(1)]).as_scalar_ref()));
+        assert!(builder.try_push(Some(strings.as_scalar_ref())).is_err());
+        let array = builder.finish();
+        assert_eq!(array.len(), 1);
+        assert_eq!(array.child().unwrap().len(), 1);
+
+        let element = DataType::List {
+            element: Box::new(DataType::Varchar),
+        };
+        let mut builder = ListArrayBuilder::with_element(1, &element).unwrap();
+        let mut ints = ListArrayBuilder::with_capacity(1);
+        ints.push(Some(int32_list(&[Some(1)]).as_scalar_ref()));
+        let nested = ListValue::new(ints.finish().into());
+        assert!(builder.try_push(Some(nested.as_scalar_ref())).is_err());
+        assert!(builder.is_empty());
+    }
+}
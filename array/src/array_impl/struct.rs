@@ -0,0 +1,518 @@
+use std::{fmt, sync::Arc};
+
+use bitvec::{slice::BitSlice, vec::BitVec};
+
+use crate::{
+    array::{Array, ArrayBuilder},
+    dispatch::{ArrayBuilderImpl, ArrayImpl, ScalarRefImpl},
+    kernel::{self, Kernel},
+    ArrayError, Bitmap, DataType, Result, Scalar, ScalarRef,
+};
+
+/// An array of composite rows, stored as one child array per named field.
+///
+/// All children have the same length as the struct array. A null row is null in
+/// every child as well.
+///
+/// The buffers are shared between clones and slices of an array. A slice holds slices of the
+/// children.
+#[derive(Debug, Clone)]
+pub struct StructArray {
+    /// The name of each field.
+    names: Arc<[String]>,
+    /// The values of each field.
+    children: Arc<[ArrayImpl]>,
+    /// The validity of each row, shared by all fields.
+    bitmap: Bitmap,
+}
+
+impl StructArray {
+    /// Create a struct array without nulls from its named columns.
+    ///
+    /// Panics if the columns have different lengths.
+    pub fn new(fields: Vec<(String, ArrayImpl)>) -> Self {
+        let len = fields.first().map_or(0, |(_, child)| child.len());
+        assert!(
+            fields.iter().all(|(_, child)| child.len() == len),
+            "struct fields have different lengths"
+        );
+        let (names, children): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        Self {
+            names: names.into(),
+            children: children.into(),
+            bitmap: Bitmap::valid(len),
+        }
+    }
+
+    pub fn field_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The values of the `index`-th field, or `None` if there is no such field.
+    pub fn field(&self, index: usize) -> Option<&ArrayImpl> {
+        self.children.get(index)
+    }
+
+    pub fn field_by_name(&self, name: &str) -> Option<&ArrayImpl> {
+        let index = self.names.iter().position(|n| n == name)?;
+        Some(&self.children[index])
+    }
+}
+
+impl Array for StructArray {
+    type RefItem<'a> = StructRef<'a>;
+    type OwnedItem = StructValue;
+    type Builder = StructArrayBuilder;
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn get(&self, index: usize) -> Option<Self::RefItem<'_>> {
+        if index < self.len() && self.bitmap.is_valid(index) {
+            Some(self.value(index))
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, index: usize) -> Self::RefItem<'_> {
+        StructRef { array: self, index }
+    }
+
+    fn validity(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        let bitmap = self.bitmap.slice(offset, len);
+        Self {
+            names: self.names.clone(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.slice(offset, len))
+                .collect(),
+            bitmap,
+        }
+    }
+
+    fn new_builder(&self, capacity: usize) -> StructArrayBuilder {
+        StructArrayBuilder {
+            names: self.names.clone(),
+            children: Some(
+                self.children
+                    .iter()
+                    .map(|child| child.new_builder(capacity))
+                    .collect(),
+            ),
+            bitmap: BitVec::with_capacity(capacity),
+        }
+    }
+}
+
+impl Kernel for StructArray {
+    /// Masks the children as well, since a null row is null in every child.
+    fn mask(&self, mask: &BitSlice) -> Self {
+        Self {
+            names: self.names.clone(),
+            children: self
+                .children
+                .iter()
+                .map(|child| kernel::mask(child, mask))
+                .collect(),
+            bitmap: self.bitmap.and(mask),
+        }
+    }
+}
+
+/// A builder of [`StructArray`]s.
+///
+/// A builder created with [`with_fields`](Self::with_fields) only accepts rows with those
+/// fields. Otherwise, the fields are the ones of the first row pushed.
+pub struct StructArrayBuilder {
+    names: Arc<[String]>,
+    /// The builders of each field, `None` until the fields are known.
+    children: Option<Vec<ArrayBuilderImpl>>,
+    bitmap: BitVec,
+}
+
+impl StructArrayBuilder {
+    /// Create a builder of rows with the name and type of each field of `fields`.
+    ///
+    /// Fails if a field type has no builder, such as a decimal with a scale out of range.
+    pub fn with_fields(capacity: usize, fields: &[(String, DataType)]) -> Result<Self> {
+        let children = fields
+            .iter()
+            .map(|(_, data_type)| ArrayBuilderImpl::with_capacity(data_type, capacity))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            names: fields.iter().map(|(name, _)| name.clone()).collect(),
+            children: Some(children),
+            bitmap: BitVec::with_capacity(capacity),
+        })
+    }
+}
+
+impl ArrayBuilder for StructArrayBuilder {
+    type Array = StructArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            names: Arc::new([]),
+            children: None,
+            bitmap: BitVec::with_capacity(capacity),
+        }
+    }
+
+    /// Append a row to builder.
+    ///
+    /// Panics if its fields don't match the fields of the builder. Use
+    /// [`try_push`](ArrayBuilder::try_push) to get an error instead.
+    fn push(&mut self, item: Option<StructRef<'_>>) {
+        if let Err(err) = self.try_push(item) {
+            panic!("{}", err);
+        }
+    }
+
+    fn try_push(&mut self, item: Option<StructRef<'_>>) -> Result<()> {
+        match item {
+            Some(row) => {
+                if self.children.is_none() {
+                    // Rows pushed so far are all null, so backfill the new children with nulls.
+                    let capacity = self.bitmap.capacity();
+                    let mut children: Vec<_> = row
+                        .array
+                        .children
+                        .iter()
+                        .map(|child| child.new_builder(capacity))
+                        .collect();
+                    for child in &mut children {
+                        child.push_n_nulls(self.bitmap.len());
+                    }
+                    self.names = row.array.names.clone();
+                    self.children = Some(children);
+                }
+                self.check(row.array)?;
+                for (child, field) in self.children.iter_mut().flatten().zip(row.iter()) {
+                    child.push(field)?;
+                }
+                self.bitmap.push(true);
+            }
+            None => {
+                for child in self.children.iter_mut().flatten() {
+                    child.push_n_nulls(1);
+                }
+                self.bitmap.push(false);
+            }
+        }
+        Ok(())
+    }
+
+    fn check(&self, array: &StructArray) -> Result<()> {
+        let Some(children) = &self.children else {
+            return Ok(());
+        };
+        if self.names != array.names {
+            return Err(ArrayError::InvalidArgument(format!(
+                "struct fields {:?} don't match {:?}",
+                array.names, self.names
+            )));
+        }
+        for (builder, child) in children.iter().zip(array.children.iter()) {
+            builder.check(child)?;
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.bitmap.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.bitmap.reserve(additional);
+        for child in self.children.iter_mut().flatten() {
+            child.reserve(additional);
+        }
+    }
+
+    fn push_n_nulls(&mut self, n: usize) {
+        for child in self.children.iter_mut().flatten() {
+            child.push_n_nulls(n);
+        }
+        self.bitmap.resize(self.bitmap.len() + n, false);
+    }
+
+    fn finish(self) -> Self::Array {
+        let children = self
+            .children
+            .map(|children| children.into_iter().map(|child| child.finish()).collect())
+            .unwrap_or_default();
+
+        StructArray {
+            names: self.names,
+            children,
+            bitmap: Bitmap::from_bits(self.bitmap),
+        }
+    }
+}
+
+/// A reference to a row of a [`StructArray`], or to a [`StructValue`].
+#[derive(Clone, Copy)]
+pub struct StructRef<'a> {
+    array: &'a StructArray,
+    index: usize,
+}
+
+impl<'a> StructRef<'a> {
+    /// Number of fields in this row.
+    pub fn len(&self) -> usize {
+        self.array.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.array.children.is_empty()
+    }
+
+    pub fn field_names(&self) -> &'a [String] {
+        &self.array.names
+    }
+
+    /// Get the value of the `index`-th field, or `None` if it is null or there is no such field.
+    pub fn field(&self, index: usize) -> Option<ScalarRefImpl<'a>> {
+        self.array.children.get(index)?.get(self.index)
+    }
+
+    pub fn field_by_name(&self, name: &str) -> Option<ScalarRefImpl<'a>> {
+        self.array.field_by_name(name)?.get(self.index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<ScalarRefImpl<'a>>> + 'a {
+        let index = self.index;
+        self.array
+            .children
+            .iter()
+            .map(move |child| child.get(index))
+    }
+}
+
+impl fmt::Debug for StructRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.field_names().iter().zip(self.iter()))
+            .finish()
+    }
+}
+
+impl PartialEq for StructRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.field_names() == other.field_names() && self.iter().eq(other.iter())
+    }
+}
+
+/// An owned struct row, stored as a [`StructArray`] with a single row.
+#[derive(Clone)]
+pub struct StructValue {
+    row: Box<StructArray>,
+}
+
+impl StructValue {
+    /// Create a struct value from named single-row columns.
+    ///
+    /// Panics if any column doesn't have exactly one row.
+    pub fn new(fields: Vec<(String, ArrayImpl)>) -> Self {
+        assert!(
+            fields.iter().all(|(_, value)| value.len() == 1),
+            "struct value fields must have exactly one row"
+        );
+        let mut row = StructArray::new(fields);
+        // A struct without fields still has its single row.
+        row.bitmap = Bitmap::valid(1);
+        Self { row: Box::new(row) }
+    }
+}
+
+impl fmt::Debug for StructValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_scalar_ref().fmt(f)
+    }
+}
+
+impl PartialEq for StructValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_scalar_ref() == other.as_scalar_ref()
+    }
+}
+
+impl Scalar for StructValue {
+    type RefType<'a> = StructRef<'a>;
+
+    type ArrayTpye = StructArray;
+
+    fn as_scalar_ref<'a>(&'a self) -> Self::RefType<'a> {
+        StructRef {
+            array: &self.row,
+            index: 0,
+        }
+    }
+    fn upcast_to<'a, 'b: 'a>(ref_item: Self::RefType<'b>) -> Self::RefType<'a> {
+        ref_item
+    }
+}
+
+impl<'a> ScalarRef<'a> for StructRef<'a> {
+    type ScalarType = StructValue;
+
+    type ArrayType = StructArray;
+
+    fn to_scalar_owned(&self) -> Self::ScalarType {
+        let mut builder = StructArrayBuilder::with_capacity(1);
+        builder.push(Some(*self));
+        StructValue {
+            row: Box::new(builder.finish()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{array_impl::StringArray, prelude::Int32Array};
+
+    fn people() -> StructArray {
+        let names = StringArray::from_slice(&[Some("alice"), Some("bob"), None]);
+        let mut ages = <Int32Array as Array>::Builder::with_capacity(3);
+        ages.push(Some(30));
+        ages.push(None);
+        ages.push(Some(7));
+        StructArray::new(vec![
+            ("name".to_string(), names.into()),
+            ("age".to_string(), ages.finish().into()),
+        ])
+    }
+
+    #[test]
+    fn struct_array_test() {
+        let array = people();
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.field_names(), ["name", "age"]);
+
+        let row = array.get(1).unwrap();
+        assert_eq!(row.field(0), Some(ScalarRefImpl::String("bob")));
+        assert_eq!(row.field(2), None);
+        assert!(array.field(2).is_none());
+        assert_eq!(row.field_by_name("age"), None);
+        assert_eq!(row.field_by_name("height"), None);
+        assert_eq!(
+            row.iter().collect::<Vec<_>>(),
+            vec![Some(ScalarRefImpl::String("bob")), None]
+        );
+    }
+
+    #[test]
+    fn struct_builder_test() {
+        let people = people();
+
+        let mut builder = StructArrayBuilder::with_capacity(4);
+        builder.push(None);
+        builder.push(people.get(2));
+        let owned = people.get(0).unwrap().to_scalar_owned();
+        builder.push(Some(owned.as_scalar_ref()));
+        builder.push(None);
+        let array: ArrayImpl = builder.finish().into();
+
+        assert_eq!(array.get(0), None);
+        assert_eq!(
+            array.get(1),
+            Some(ScalarRefImpl::Struct(people.get(2).unwrap()))
+        );
+        assert_eq!(
+            array.get(2),
+            Some(ScalarRefImpl::Struct(owned.as_scalar_ref()))
+        );
+        assert_eq!(array.get(3), None);
+
+        let array: StructArray = array.try_into().unwrap();
+        assert_eq!(array.field(1).unwrap().len(), 4);
+        assert_eq!(array.field(1).unwrap().get(0), None);
+
+        let mut builder = StructArrayBuilder::with_capacity(0);
+        builder.push_n_nulls(1);
+        builder.append_array(&people.slice(1, 2));
+        builder.push_n_nulls(2);
+        assert_eq!(builder.len(), 5);
+        let array = builder.finish();
+        assert_eq!(array.field(0).unwrap().len(), 5);
+        assert_eq!(array.get(1), people.get(1));
+        assert_eq!(array.get(4), None);
+    }
+
+    #[test]
+    fn struct_typed_builder_test() {
+        let fields = [
+            ("name".to_string(), DataType::Varchar),
+            ("age".to_string(), DataType::Integer),
+        ];
+        let mut builder = StructArrayBuilder::with_fields(2, &fields).unwrap();
+        builder.push_n_nulls(2);
+        let array = builder.finish();
+        assert_eq!(array.field_names(), ["name", "age"]);
+        assert_eq!(array.field(1).unwrap().type_name(), "Int32Array");
+        assert_eq!(array.field(1).unwrap().len(), 2);
+
+        let mut builder = StructArrayBuilder::with_fields(2, &fields).unwrap();
+        builder.push(people().get(0));
+        let renamed = StructArray::new(vec![
+            (
+                "name".to_string(),
+                StringArray::from_slice(&[Some("a")]).into(),
+            ),
+            (
+                "height".to_string(),
+                StringArray::from_slice(&[Some("b")]).into(),
+            ),
+        ]);
+        assert!(builder.try_push(renamed.get(0)).is_err());
+        let retyped = StructArray::new(vec![
+            (
+                "name".to_string(),
+                StringArray::from_slice(&[Some("a")]).into(),
+            ),
+            (
+                "age".to_string(),
+                StringArray::from_slice(&[Some("b")]).into(),
+            ),
+        ]);
+        assert!(builder.try_push(retyped.get(0)).is_err());
+        assert_eq!(builder.len(), 1);
+        let array = builder.finish();
+        assert_eq!(array.field(0).unwrap().len(), 1);
+        assert_eq!(array.field(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn struct_slice_test() {
+        let array = people().slice(1, 2);
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.field(0).unwrap().len(), 2);
+        let row = array.get(1).unwrap();
+        assert_eq!(row.field(0), None);
+        assert_eq!(row.field_by_name("age"), Some(ScalarRefImpl::Int32(7)));
+        assert_eq!(array.get(2), None);
+    }
+
+    #[test]
+    fn struct_value_test() {
+        let mut age = <Int32Array as Array>::Builder::with_capacity(1);
+        age.push(Some(30));
+        let value = StructValue::new(vec![
+            (
+                "name".to_string(),
+                StringArray::from_slice(&[Some("alice")]).into(),
+            ),
+            ("age".to_string(), age.finish().into()),
+        ]);
+        assert_eq!(value, people().get(0).unwrap().to_scalar_owned());
+        assert_ne!(value, people().get(1).unwrap().to_scalar_owned());
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    ops::Range,
+    sync::{Arc, OnceLock},
+};
+
+use bitvec::{field::BitField, slice::BitSlice, vec::BitVec};
+
+/// The validity of the values of an array. If the bit is set, the corresponding value is not
+/// null.
+///
+/// A bitmap without nulls has no buffer at all. Otherwise the buffer is shared between clones and
+/// slices of a bitmap.
+#[derive(Debug, Clone)]
+pub struct Bitmap {
+    /// `None` if every bit is set.
+    bits: Option<Arc<BitVec>>,
+    /// The first bit of `bits` covered by this bitmap.
+    offset: usize,
+    len: usize,
+    /// Number of unset bits, counted on first use.
+    null_count: OnceLock<usize>,
+}
+
+impl Bitmap {
+    /// A bitmap of `len` valid values.
+    pub fn valid(len: usize) -> Self {
+        Self {
+            bits: None,
+            offset: 0,
+            len,
+            null_count: OnceLock::from(0),
+        }
+    }
+
+    /// A bitmap of the validity of each value, dropping the buffer if no value is null.
+    pub fn from_bits(bits: BitVec) -> Self {
+        let null_count = bits.count_zeros();
+        Self {
+            len: bits.len(),
+            offset: 0,
+            bits: (null_count > 0).then(|| Arc::new(bits)),
+            null_count: OnceLock::from(null_count),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the `index`-th value is not null.
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn is_valid(&self, index: usize) -> bool {
+        assert!(index < self.len, "bitmap index out of bounds");
+        match &self.bits {
+            Some(bits) => bits[self.offset + index],
+            None => true,
+        }
+    }
+
+    pub fn null_count(&self) -> usize {
+        *self.null_count.get_or_init(|| match self.as_bitslice() {
+            Some(bits) => bits.count_zeros(),
+            None => 0,
+        })
+    }
+
+    /// Whether no value is null, in which case per-value checks can be skipped.
+    pub fn all_valid(&self) -> bool {
+        self.null_count() == 0
+    }
+
+    /// The bits of this bitmap, or `None` if every bit is set.
+    pub fn as_bitslice(&self) -> Option<&BitSlice> {
+        let bits = self.bits.as_deref()?;
+        Some(&bits[self.offset..self.offset + self.len])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.is_valid(i))
+    }
+
+    /// Get `len` bits starting at `offset`, without copying them.
+    ///
+    /// Panics if the bits are out of bounds.
+    pub fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            bits: self.bits.clone(),
+            offset: self.offset + offset,
+            len,
+            null_count: match self.bits {
+                Some(_) => OnceLock::new(),
+                None => OnceLock::from(0),
+            },
+        }
+    }
+
+    /// The bits at `indices`, which must be in bounds.
+    pub(crate) fn gather(&self, indices: &[usize]) -> Self {
+        match self.as_bitslice() {
+            Some(bits) => Self::from_bits(indices.iter().map(|&i| bits[i]).collect()),
+            None => Self::valid(indices.len()),
+        }
+    }
+
+    /// The bits set both in this bitmap and in `bits`, which must have the same length.
+    pub(crate) fn and(&self, bits: &BitSlice) -> Self {
+        assert_eq!(bits.len(), self.len, "bitmap length mismatch");
+        let words = self.words().zip(words(bits)).map(|(a, b)| a & b);
+        Self::from_bits(from_words(words, self.len))
+    }
+
+    /// The bits of all `bitmaps`, end to end.
+    pub(crate) fn concat<'a>(bitmaps: impl IntoIterator<Item = &'a Bitmap> + Clone) -> Self {
+        let len = bitmaps.clone().into_iter().map(Bitmap::len).sum();
+        if bitmaps.clone().into_iter().all(Bitmap::all_valid) {
+            return Self::valid(len);
+        }
+        let mut bits = BitVec::with_capacity(len);
+        for bitmap in bitmaps {
+            bitmap.extend_to(&mut bits, 0..bitmap.len());
+        }
+        Self::from_bits(bits)
+    }
+
+    /// The bits of this bitmap, a word at a time, as given by [`words`] except that the padding of
+    /// the last word is unspecified.
+    pub(crate) fn words(&self) -> impl Iterator<Item = usize> + '_ {
+        let bits = self.as_bitslice();
+        (0..self.len.div_ceil(WORD_BITS)).map(move |i| match bits {
+            Some(bits) => bits[i * WORD_BITS..self.len.min((i + 1) * WORD_BITS)].load_le(),
+            None => usize::MAX,
+        })
+    }
+
+    /// Append the bits in `range` to `bits`.
+    pub(crate) fn extend_to(&self, bits: &mut BitVec, range: Range<usize>) {
+        match self.as_bitslice() {
+            Some(slice) => bits.extend_from_bitslice(&slice[range]),
+            None => {
+                assert!(range.end <= self.len, "slice out of bounds");
+                bits.resize(bits.len() + range.len(), true);
+            }
+        }
+    }
+}
+
+const WORD_BITS: usize = usize::BITS as usize;
+
+/// The bits of `bits`, a word at a time. Bit `i` of the slice is bit `i % usize::BITS` of word
+/// `i / usize::BITS`, and the last word is padded with zeros.
+pub(crate) fn words(bits: &BitSlice) -> impl Iterator<Item = usize> + '_ {
+    bits.chunks(WORD_BITS).map(BitField::load_le)
+}
+
+/// Collect the first `len` bits of `words`, laid out as by [`words`].
+pub(crate) fn from_words(words: impl IntoIterator<Item = usize>, len: usize) -> BitVec {
+    let mut bits = BitVec::from_vec(words.into_iter().collect());
+    bits.truncate(len);
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_bitmap() {
+        let bitmap = Bitmap::from_bits(bitvec![1, 0, 1, 1, 0]);
+        assert_eq!(bitmap.len(), 5);
+        assert_eq!(bitmap.null_count(), 2);
+        assert!(!bitmap.is_valid(1));
+
+        let slice = bitmap.slice(2, 2);
+        assert!(slice.all_valid());
+        assert_eq!(slice.iter().collect::<Vec<_>>(), [true, true]);
+        assert_eq!(bitmap.slice(3, 2).null_count(), 1);
+
+        let masked = bitmap.and(bits![1, 1, 0, 1, 1]);
+        assert_eq!(
+            masked.iter().collect::<Vec<_>>(),
+            [true, false, false, true, false]
+        );
+        assert!(Bitmap::valid(2).and(bits![1, 1]).as_bitslice().is_none());
+
+        let gathered = bitmap.gather(&[4, 0]);
+        assert_eq!(gathered.iter().collect::<Vec<_>>(), [false, true]);
+        assert!(bitmap.gather(&[0, 2]).as_bitslice().is_none());
+
+        let concat = Bitmap::concat([&Bitmap::valid(2), &slice, &bitmap.slice(0, 2)]);
+        assert_eq!(
+            concat.iter().collect::<Vec<_>>(),
+            [true, true, true, true, true, false]
+        );
+        assert!(Bitmap::concat([&Bitmap::valid(2), &slice])
+            .as_bitslice()
+            .is_none());
+    }
+
+    #[test]
+    fn test_all_valid() {
+        let bitmap = Bitmap::from_bits(bitvec![1; 3]);
+        assert!(bitmap.as_bitslice().is_none());
+        assert!(bitmap.all_valid());
+        assert!(bitmap.is_valid(2));
+
+        let mut bits = BitVec::new();
+        bitmap.extend_to(&mut bits, 1..3);
+        Bitmap::from_bits(bitvec![0, 1]).extend_to(&mut bits, 0..1);
+        assert_eq!(bits, bitvec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_words() {
+        let bits: BitVec = (0..100).map(|i| i % 3 == 0).collect();
+        let shifted = &bits[5..];
+        assert_eq!(from_words(words(shifted), shifted.len()), shifted);
+
+        let bitmap = Bitmap::from_bits(bits.clone()).slice(5, 95);
+        assert_eq!(from_words(bitmap.words(), bitmap.len()), shifted);
+        let valid = Bitmap::valid(70);
+        assert_eq!(from_words(valid.words(), 70), bitvec![1; 70]);
+    }
+}
@@ -0,0 +1,681 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::{
+    ArrayError, ArrayImpl, DataType, Date, Datum, Decimal, Expression, Interval, Result, Scalar,
+    ScalarRef, Time, Timestamp, UnaryExpression,
+};
+
+/// Conversion from a scalar of another type, as done by `CAST`.
+pub trait CastFrom<T>: Sized {
+    fn cast_from(value: T) -> Result<Self>;
+}
+
+/// Casts to the same type, which only copy the value.
+macro_rules! impl_cast_from_self {
+    ($($ty:ty),*) => {
+        $(
+            impl CastFrom<$ty> for $ty {
+                fn cast_from(value: $ty) -> Result<Self> {
+                    Ok(value.to_scalar_owned())
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_from_self!(bool, i16, i32, i64, f32, f64, Decimal, Date, Time, Timestamp, Interval);
+
+impl CastFrom<&str> for String {
+    fn cast_from(value: &str) -> Result<Self> {
+        Ok(value.to_scalar_owned())
+    }
+}
+
+impl CastFrom<&[u8]> for Vec<u8> {
+    fn cast_from(value: &[u8]) -> Result<Self> {
+        Ok(value.to_scalar_owned())
+    }
+}
+
+/// Lossless casts.
+macro_rules! impl_cast_from_into {
+    ($($from:ty => $($to:ty),*);*) => {
+        $($(
+            impl CastFrom<$from> for $to {
+                fn cast_from(value: $from) -> Result<Self> {
+                    Ok(value.into())
+                }
+            }
+        )*)*
+    };
+}
+
+impl_cast_from_into! {
+    i16 => i32, i64, f32, f64, Decimal;
+    i32 => i64, f64, Decimal;
+    i64 => Decimal;
+    f32 => f64;
+    Date => Timestamp
+}
+
+/// Integer narrowing, which fails if the value is out of range.
+macro_rules! impl_cast_from_int_narrowing {
+    ($($from:ty => $($to:ty),*);*) => {
+        $($(
+            impl CastFrom<$from> for $to {
+                fn cast_from(value: $from) -> Result<Self> {
+                    <$to>::try_from(value).map_err(|_| {
+                        ArrayError::Overflow(format!("{} as {}", value, stringify!($to)))
+                    })
+                }
+            }
+        )*)*
+    };
+}
+
+impl_cast_from_int_narrowing! {
+    i32 => i16;
+    i64 => i16, i32
+}
+
+/// Integer to float casts that may lose precision.
+macro_rules! impl_cast_from_int_to_float {
+    ($($from:ty => $($to:ty),*);*) => {
+        $($(
+            impl CastFrom<$from> for $to {
+                fn cast_from(value: $from) -> Result<Self> {
+                    Ok(value as $to)
+                }
+            }
+        )*)*
+    };
+}
+
+impl_cast_from_int_to_float! {
+    i32 => f32;
+    i64 => f32, f64
+}
+
+/// Float to integer casts, which round to the nearest integer and fail if it is out of range.
+macro_rules! impl_cast_from_float_to_int {
+    ($($from:ty => $($to:ty),*);*) => {
+        $($(
+            impl CastFrom<$from> for $to {
+                fn cast_from(value: $from) -> Result<Self> {
+                    let rounded = value.round();
+                    // `MIN` is a power of two, so both bounds are exact. NaN fails both checks.
+                    if (<$to>::MIN as $from) <= rounded && rounded < -(<$to>::MIN as $from) {
+                        Ok(rounded as $to)
+                    } else {
+                        Err(ArrayError::Overflow(format!("{} as {}", value, stringify!($to))))
+                    }
+                }
+            }
+        )*)*
+    };
+}
+
+impl_cast_from_float_to_int! {
+    f32 => i16, i32, i64;
+    f64 => i16, i32, i64
+}
+
+impl CastFrom<f64> for f32 {
+    fn cast_from(value: f64) -> Result<Self> {
+        let result = value as f32;
+        if result.is_infinite() && value.is_finite() {
+            return Err(ArrayError::Overflow(format!("{} as f32", value)));
+        }
+        Ok(result)
+    }
+}
+
+macro_rules! impl_cast_from_float_to_decimal {
+    ($($from:ty),*) => {
+        $(
+            impl CastFrom<$from> for Decimal {
+                fn cast_from(value: $from) -> Result<Self> {
+                    if !value.is_finite() {
+                        return Err(ArrayError::InvalidArgument(format!(
+                            "cannot cast {} to decimal",
+                            value
+                        )));
+                    }
+                    // The shortest representation that round-trips, which is what users expect
+                    // rather than the exact binary value.
+                    value
+                        .to_string()
+                        .parse()
+                        .map_err(|_| ArrayError::Overflow(format!("{} as decimal", value)))
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_from_float_to_decimal!(f32, f64);
+
+macro_rules! impl_cast_from_decimal_to_int {
+    ($($to:ty),*) => {
+        $(
+            impl CastFrom<Decimal> for $to {
+                fn cast_from(value: Decimal) -> Result<Self> {
+                    value
+                        .rescale(0)
+                        .and_then(|value| <$to>::try_from(value.mantissa()).ok())
+                        .ok_or_else(|| {
+                            ArrayError::Overflow(format!("{} as {}", value, stringify!($to)))
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_from_decimal_to_int!(i16, i32, i64);
+
+impl CastFrom<Decimal> for f32 {
+    fn cast_from(value: Decimal) -> Result<Self> {
+        Ok(value.to_f64() as f32)
+    }
+}
+
+impl CastFrom<Decimal> for f64 {
+    fn cast_from(value: Decimal) -> Result<Self> {
+        Ok(value.to_f64())
+    }
+}
+
+macro_rules! impl_cast_between_bool_and_int {
+    ($($ty:ty),*) => {
+        $(
+            impl CastFrom<bool> for $ty {
+                fn cast_from(value: bool) -> Result<Self> {
+                    Ok(value as $ty)
+                }
+            }
+
+            impl CastFrom<$ty> for bool {
+                fn cast_from(value: $ty) -> Result<Self> {
+                    Ok(value != 0)
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_between_bool_and_int!(i16, i32, i64);
+
+impl CastFrom<Timestamp> for Date {
+    fn cast_from(value: Timestamp) -> Result<Self> {
+        Ok(value.date())
+    }
+}
+
+impl CastFrom<Timestamp> for Time {
+    fn cast_from(value: Timestamp) -> Result<Self> {
+        Ok(value.time())
+    }
+}
+
+/// Casts to strings, which format the value.
+macro_rules! impl_cast_to_string {
+    ($($from:ty),*) => {
+        $(
+            impl CastFrom<$from> for String {
+                fn cast_from(value: $from) -> Result<Self> {
+                    Ok(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_to_string!(bool, i16, i32, i64, f32, f64, Decimal, Date, Time, Timestamp, Interval);
+
+/// Casts from strings, which parse the value ignoring surrounding whitespace.
+macro_rules! impl_cast_from_string {
+    ($($to:ty => $name:literal),*) => {
+        $(
+            impl CastFrom<&str> for $to {
+                fn cast_from(value: &str) -> Result<Self> {
+                    parse(value, $name)
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_from_string! {
+    i16 => "smallint",
+    i32 => "integer",
+    i64 => "bigint",
+    f32 => "real",
+    f64 => "double",
+    Decimal => "decimal",
+    Date => "date",
+    Time => "time",
+    Timestamp => "timestamp"
+}
+
+fn parse<T: FromStr>(value: &str, target: &'static str) -> Result<T> {
+    value.trim().parse().map_err(|_| ArrayError::Parse {
+        target,
+        input: value.to_string(),
+    })
+}
+
+impl CastFrom<&str> for bool {
+    fn cast_from(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "t" | "true" | "y" | "yes" | "on" | "1" => Ok(true),
+            "f" | "false" | "n" | "no" | "off" | "0" => Ok(false),
+            _ => Err(ArrayError::Parse {
+                target: "boolean",
+                input: value.to_string(),
+            }),
+        }
+    }
+}
+
+/// Bytes are formatted in hex, as in `\x0aff`.
+impl CastFrom<&[u8]> for String {
+    fn cast_from(value: &[u8]) -> Result<Self> {
+        let mut s = String::with_capacity(2 + value.len() * 2);
+        s.push_str("\\x");
+        for byte in value {
+            write!(s, "{:02x}", byte).unwrap();
+        }
+        Ok(s)
+    }
+}
+
+/// A string in hex format, as in `\x0aff`, is decoded. Any other string is taken as is.
+impl CastFrom<&str> for Vec<u8> {
+    fn cast_from(value: &str) -> Result<Self> {
+        let hex = match value.strip_prefix("\\x") {
+            Some(hex) => hex,
+            None => return Ok(value.as_bytes().to_vec()),
+        };
+        let invalid = || ArrayError::Parse {
+            target: "bytea",
+            input: value.to_string(),
+        };
+        if hex.len() % 2 != 0 {
+            return Err(invalid());
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(invalid)
+            })
+            .collect()
+    }
+}
+
+/// `CAST(value AS O)`.
+pub fn cast<I: Scalar, O>(value: I::RefType<'_>) -> Result<O>
+where
+    O: Scalar + for<'a> CastFrom<I::RefType<'a>>,
+{
+    O::cast_from(value)
+}
+
+/// `TRY_CAST(value AS O)`, which returns null when the cast fails.
+pub fn try_cast<I: Scalar, O>(value: I::RefType<'_>) -> Option<O>
+where
+    O: Scalar + for<'a> CastFrom<I::RefType<'a>>,
+{
+    O::cast_from(value).ok()
+}
+
+/// Pad a string with spaces, or truncate it, to `width` characters, as `CHAR(width)` stores it.
+pub fn fit_char(value: &str, width: u16) -> String {
+    let width = width as usize;
+    let mut s: String = value.chars().take(width).collect();
+    let len = s.chars().count();
+    s.extend(std::iter::repeat_n(' ', width - len));
+    s
+}
+
+/// Round a decimal to `scale`, failing if it then has more than `precision` digits.
+pub fn fit_decimal(value: Decimal, scale: u16, precision: u16) -> Result<Decimal> {
+    u8::try_from(scale)
+        .ok()
+        .and_then(|scale| value.rescale(scale))
+        .filter(|value| value.precision() as u16 <= precision)
+        .ok_or_else(|| {
+            ArrayError::Overflow(format!("{} as decimal({}, {})", value, precision, scale))
+        })
+}
+
+macro_rules! impl_cast_for {
+    ($cast_func: ident, $from: ident, $to: ident, $({$ty1: tt, $ty2: tt}),*) => {
+        match ($from, $to) {
+            $(
+                ($ty1!{ data_type_pattern }, $ty2!{ data_type_pattern }) => {
+                    Ok(Box::new(UnaryExpression::<$ty1!{ scalar_type }, _, _>::new(
+                        $cast_func::<$ty1!{ scalar_type }, $ty2!{ scalar_type }>
+                    )))
+                }
+            )*
+            (from, to) => {
+                Err(ArrayError::Unsupported(format!("cast from {:?} to {:?}", from, to)))
+            }
+        }
+    };
+}
+
+/// Source and target types of all casts. Casts to `Char` produce strings of any width, and casts
+/// to `Decimal` produce decimals of any scale; both are fitted to the target type afterwards.
+macro_rules! for_all_cast {
+    ($macro: tt, $($param:ident),*) => {
+        $macro! {
+            $($param),*,
+            {boolean, boolean},
+            {boolean, int16},
+            {boolean, int32},
+            {boolean, int64},
+            {boolean, varchar},
+            {boolean, char},
+            {int16, boolean},
+            {int16, int16},
+            {int16, int32},
+            {int16, int64},
+            {int16, float32},
+            {int16, float64},
+            {int16, decimal},
+            {int16, varchar},
+            {int16, char},
+            {int32, boolean},
+            {int32, int16},
+            {int32, int32},
+            {int32, int64},
+            {int32, float32},
+            {int32, float64},
+            {int32, decimal},
+            {int32, varchar},
+            {int32, char},
+            {int64, boolean},
+            {int64, int16},
+            {int64, int32},
+            {int64, int64},
+            {int64, float32},
+            {int64, float64},
+            {int64, decimal},
+            {int64, varchar},
+            {int64, char},
+            {float32, int16},
+            {float32, int32},
+            {float32, int64},
+            {float32, float32},
+            {float32, float64},
+            {float32, decimal},
+            {float32, varchar},
+            {float32, char},
+            {float64, int16},
+            {float64, int32},
+            {float64, int64},
+            {float64, float32},
+            {float64, float64},
+            {float64, decimal},
+            {float64, varchar},
+            {float64, char},
+            {decimal, int16},
+            {decimal, int32},
+            {decimal, int64},
+            {decimal, float32},
+            {decimal, float64},
+            {decimal, decimal},
+            {decimal, varchar},
+            {decimal, char},
+            {date, date},
+            {date, timestamp},
+            {date, varchar},
+            {date, char},
+            {time, time},
+            {time, varchar},
+            {time, char},
+            {timestamp, date},
+            {timestamp, time},
+            {timestamp, timestamp},
+            {timestamp, varchar},
+            {timestamp, char},
+            {interval, interval},
+            {interval, varchar},
+            {interval, char},
+            {varchar, boolean},
+            {varchar, int16},
+            {varchar, int32},
+            {varchar, int64},
+            {varchar, float32},
+            {varchar, float64},
+            {varchar, decimal},
+            {varchar, date},
+            {varchar, time},
+            {varchar, timestamp},
+            {varchar, varchar},
+            {varchar, char},
+            {varchar, bytea},
+            {char, boolean},
+            {char, int16},
+            {char, int32},
+            {char, int64},
+            {char, float32},
+            {char, float64},
+            {char, decimal},
+            {char, date},
+            {char, time},
+            {char, timestamp},
+            {char, varchar},
+            {char, char},
+            {char, bytea},
+            {bytea, varchar},
+            {bytea, char},
+            {bytea, bytea}
+        }
+    };
+}
+
+/// Build a `CAST(x AS to)` expression for an input of type `from`.
+/// The expression fails on values that can't be converted.
+pub fn build_cast(from: &DataType, to: &DataType) -> Result<Box<dyn Expression>> {
+    let cast = cast_expression(from, to)?;
+    Ok(match *to {
+        DataType::Char { width } => Box::new(ThenExpression {
+            first: cast,
+            then: Box::new(UnaryExpression::<String, _, _>::new(move |s: &str| {
+                fit_char(s, width)
+            })),
+        }),
+        DataType::Decimal { scale, precision } => Box::new(ThenExpression {
+            first: cast,
+            then: Box::new(UnaryExpression::<Decimal, _, _>::new(move |d| {
+                fit_decimal(d, scale, precision)
+            })),
+        }),
+        _ => cast,
+    })
+}
+
+/// Build a `TRY_CAST(x AS to)` expression for an input of type `from`.
+/// The expression returns null for values that can't be converted.
+pub fn build_try_cast(from: &DataType, to: &DataType) -> Result<Box<dyn Expression>> {
+    let cast = try_cast_expression(from, to)?;
+    Ok(match *to {
+        DataType::Char { width } => Box::new(ThenExpression {
+            first: cast,
+            then: Box::new(UnaryExpression::<String, _, _>::new(move |s: &str| {
+                fit_char(s, width)
+            })),
+        }),
+        DataType::Decimal { scale, precision } => Box::new(ThenExpression {
+            first: cast,
+            then: Box::new(UnaryExpression::<Decimal, _, _>::new(move |d| {
+                fit_decimal(d, scale, precision).ok()
+            })),
+        }),
+        _ => cast,
+    })
+}
+
+fn cast_expression(from: &DataType, to: &DataType) -> Result<Box<dyn Expression>> {
+    for_all_cast! { impl_cast_for, cast, from, to }
+}
+
+fn try_cast_expression(from: &DataType, to: &DataType) -> Result<Box<dyn Expression>> {
+    for_all_cast! { impl_cast_for, try_cast, from, to }
+}
+
+/// Evaluates `first`, then `then` over its result.
+struct ThenExpression {
+    first: Box<dyn Expression>,
+    then: Box<dyn Expression>,
+}
+
+impl Expression for ThenExpression {
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        let result = self.first.eval_datums(inputs)?;
+        self.then.eval_datums(&[Datum::Array(&result)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        array_impl::StringArray,
+        prelude::{Float64ArrayBuilder, Int64ArrayBuilder},
+        ArrayBuilder, ScalarRefImpl,
+    };
+
+    #[test]
+    fn test_cast_numeric() {
+        let mut ints = Int64ArrayBuilder::with_capacity(3);
+        ints.push(Some(7));
+        ints.push(None);
+        ints.push(Some(40000));
+        let ints: ArrayImpl = ints.finish().into();
+
+        let expr = build_cast(&DataType::BigInt, &DataType::Integer).unwrap();
+        let result = expr.eval_batch(&[&ints]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(7)));
+        assert_eq!(result.get(1), None);
+
+        let err = build_cast(&DataType::BigInt, &DataType::SmallInt)
+            .unwrap()
+            .eval_batch(&[&ints])
+            .unwrap_err();
+        assert_eq!(err, ArrayError::Overflow("40000 as i16".to_string()));
+
+        let expr = build_try_cast(&DataType::BigInt, &DataType::SmallInt).unwrap();
+        let result = expr.eval_batch(&[&ints]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int16(7)));
+        assert_eq!(result.get(2), None);
+
+        let mut floats = Float64ArrayBuilder::with_capacity(3);
+        floats.push(Some(2.5));
+        floats.push(Some(-2.5));
+        floats.push(Some(f64::NAN));
+        let floats: ArrayImpl = floats.finish().into();
+
+        let expr = build_try_cast(&DataType::Double, &DataType::Integer).unwrap();
+        let result = expr.eval_batch(&[&floats]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(3)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Int32(-3)));
+        assert_eq!(result.get(2), None);
+
+        let to_decimal = DataType::Decimal {
+            scale: 1,
+            precision: 2,
+        };
+        let expr = build_try_cast(&DataType::Double, &to_decimal).unwrap();
+        let result = expr.eval_batch(&[&floats]).unwrap();
+        assert_eq!(
+            result.get(0),
+            Some(ScalarRefImpl::Decimal(Decimal::new(25, 1).unwrap()))
+        );
+        let result = expr
+            .eval_datums(&[Datum::Scalar(Some(ScalarRefImpl::Float64(12.5)))])
+            .unwrap();
+        assert_eq!(result.get(0), None);
+    }
+
+    #[test]
+    fn test_cast_string() {
+        let strings: ArrayImpl =
+            StringArray::from_slice(&[Some(" 12 "), Some("yes"), Some("2022-03-01"), None]).into();
+
+        let expr = build_try_cast(&DataType::Varchar, &DataType::Integer).unwrap();
+        let result = expr.eval_batch(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(12)));
+        assert_eq!(result.get(1), None);
+
+        let err = build_cast(&DataType::Varchar, &DataType::Boolean)
+            .unwrap()
+            .eval_batch(&[&strings])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input syntax for boolean: \" 12 \""
+        );
+
+        let expr = build_try_cast(&DataType::Varchar, &DataType::Date).unwrap();
+        let result = expr.eval_batch(&[&strings]).unwrap();
+        assert_eq!(
+            result.get(2),
+            Some(ScalarRefImpl::Date(Date::from_ymd(2022, 3, 1).unwrap()))
+        );
+
+        let expr = build_cast(&DataType::Varchar, &DataType::Char { width: 4 }).unwrap();
+        let result = expr.eval_batch(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String(" 12 ")));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("yes ")));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::String("2022")));
+        assert_eq!(result.get(3), None);
+
+        let expr = build_cast(&DataType::Boolean, &DataType::Varchar).unwrap();
+        let result = expr
+            .eval_datums(&[Datum::Scalar(Some(ScalarRefImpl::Boolean(true)))])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("true")));
+    }
+
+    #[test]
+    fn test_cast_decimal_overflow() {
+        // No single scale holds both values before they are rounded to the target type, so the
+        // cast fails rather than panics.
+        let strings: ArrayImpl = StringArray::from_slice(&[
+            Some("0.00000000000000000000000000000000000001"),
+            Some("1000"),
+        ])
+        .into();
+        let to_decimal = DataType::Decimal {
+            scale: 2,
+            precision: 10,
+        };
+        let err = build_try_cast(&DataType::Varchar, &to_decimal)
+            .unwrap()
+            .eval_batch(&[&strings])
+            .unwrap_err();
+        assert!(matches!(err, ArrayError::Overflow(_)));
+    }
+
+    #[test]
+    fn test_cast_bytes() {
+        let bytes = <Vec<u8>>::cast_from("\\x0aff").unwrap();
+        assert_eq!(bytes, [0x0a, 0xff]);
+        assert_eq!(String::cast_from(&bytes[..]).unwrap(), "\\x0aff");
+        assert_eq!(<Vec<u8>>::cast_from("ab").unwrap(), b"ab");
+        assert!(<Vec<u8>>::cast_from("\\x0").is_err());
+    }
+
+    #[test]
+    fn test_cast_unsupported() {
+        assert!(build_cast(&DataType::Date, &DataType::Integer).is_err());
+        assert!(build_try_cast(&DataType::Bytea, &DataType::Boolean).is_err());
+    }
+}
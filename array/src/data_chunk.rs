@@ -0,0 +1,466 @@
+use bitvec::vec::BitVec;
+
+use crate::{
+    bitmap::{from_words, words},
+    kernel,
+    prelude::BooleanArray,
+    Array, ArrayError, ArrayImpl, DataType, Result, ScalarRefImpl, SchemaRef,
+};
+
+/// A batch of rows, stored as equal-length columns described by a [`Schema`](crate::Schema).
+///
+/// Rows can be filtered out without touching the columns: they are then marked invisible in a
+/// visibility bitmap, and skipped by [`rows`](Self::rows) and
+/// [`BoundExpr::eval_chunk`](crate::BoundExpr::eval_chunk). [`compact`](Self::compact) drops them
+/// from the columns.
+#[derive(Debug, Clone)]
+pub struct DataChunk {
+    schema: SchemaRef,
+    columns: Vec<ArrayImpl>,
+    /// Number of rows in the columns, visible or not.
+    capacity: usize,
+    /// If the bit is set, the corresponding row is visible. `None` if every row is.
+    visibility: Option<BitVec>,
+}
+
+impl DataChunk {
+    /// Create a chunk from its columns, checking them against `schema`.
+    ///
+    /// A chunk without columns has no rows.
+    pub fn try_new(schema: SchemaRef, columns: Vec<ArrayImpl>) -> Result<Self> {
+        if columns.len() != schema.len() {
+            return Err(ArrayError::ArityMismatch {
+                expected: schema.len(),
+                actual: columns.len(),
+            });
+        }
+        let capacity = columns.first().map_or(0, ArrayImpl::len);
+        for (field, column) in schema.fields().iter().zip(&columns) {
+            if column.len() != capacity {
+                return Err(ArrayError::LengthMismatch {
+                    left: capacity,
+                    right: column.len(),
+                });
+            }
+            check_type(column, &field.data_type)
+                .map_err(|err| err.context(format!("column {}", field.name)))?;
+            if !field.nullable && column.null_count() > 0 {
+                return Err(ArrayError::InvalidArgument(format!(
+                    "null in non-nullable column {}",
+                    field.name
+                )));
+            }
+        }
+        Ok(Self {
+            schema,
+            columns,
+            capacity,
+            visibility: None,
+        })
+    }
+
+    /// Mark the rows whose bit is unset in `visibility` as invisible.
+    pub fn with_visibility(self, visibility: BitVec) -> Result<Self> {
+        if visibility.len() != self.capacity {
+            return Err(ArrayError::LengthMismatch {
+                left: self.capacity,
+                right: visibility.len(),
+            });
+        }
+        Ok(Self {
+            visibility: Some(visibility),
+            ..self
+        })
+    }
+
+    /// Hide the rows where `mask` is false or null, keeping them in place.
+    ///
+    /// `mask` has a row for every row of the chunk, visible or not.
+    pub fn filter(self, mask: &BooleanArray) -> Result<Self> {
+        if mask.len() != self.capacity {
+            return Err(ArrayError::LengthMismatch {
+                left: self.capacity,
+                right: mask.len(),
+            });
+        }
+        let mut visibility = mask.true_bits();
+        if let Some(old) = &self.visibility {
+            visibility = from_words(
+                words(&visibility).zip(words(old)).map(|(a, b)| a & b),
+                self.capacity,
+            );
+        }
+        Ok(Self {
+            visibility: Some(visibility),
+            ..self
+        })
+    }
+
+    /// Drop the invisible rows from the columns.
+    pub fn compact(self) -> Self {
+        let Some(visibility) = &self.visibility else {
+            return self;
+        };
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| compact_array(column, visibility))
+            .collect();
+        Self {
+            schema: self.schema,
+            columns,
+            capacity: visibility.count_ones(),
+            visibility: None,
+        }
+    }
+
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Number of visible rows.
+    pub fn cardinality(&self) -> usize {
+        match &self.visibility {
+            Some(visibility) => visibility.count_ones(),
+            None => self.capacity,
+        }
+    }
+
+    /// Number of rows in the columns, including the invisible ones.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn visibility(&self) -> Option<&BitVec> {
+        self.visibility.as_ref()
+    }
+
+    /// Whether the `index`-th row of the columns is visible. A row out of bounds is not.
+    pub fn is_visible(&self, index: usize) -> bool {
+        match &self.visibility {
+            Some(visibility) => visibility.get(index).is_some_and(|visible| *visible),
+            None => index < self.capacity,
+        }
+    }
+
+    /// The indices of the visible rows in the columns.
+    pub fn visible_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.capacity).filter(|&i| self.is_visible(i))
+    }
+
+    /// The columns, including the invisible rows.
+    pub fn columns(&self) -> &[ArrayImpl] {
+        &self.columns
+    }
+
+    /// The `index`-th column, including the invisible rows.
+    pub fn column(&self, index: usize) -> Result<&ArrayImpl> {
+        self.columns.get(index).ok_or_else(|| {
+            ArrayError::InvalidArgument(format!(
+                "column index {} out of bounds for {} columns",
+                index,
+                self.columns.len()
+            ))
+        })
+    }
+
+    /// The columns, without the invisible rows.
+    pub fn into_columns(self) -> Vec<ArrayImpl> {
+        self.compact().columns
+    }
+
+    /// A chunk with the columns at `indices`, in that order.
+    pub fn project(&self, indices: &[usize]) -> Result<Self> {
+        let columns = indices
+            .iter()
+            .map(|&i| self.column(i).cloned())
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            schema: self.schema.project(indices).into(),
+            columns,
+            capacity: self.capacity,
+            visibility: self.visibility.clone(),
+        })
+    }
+
+    /// The values of the `index`-th row of the columns, visible or not.
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn row(&self, index: usize) -> Vec<Option<ScalarRefImpl<'_>>> {
+        assert!(index < self.capacity, "row index out of bounds");
+        self.columns
+            .iter()
+            .map(|column| column.get(index))
+            .collect()
+    }
+
+    /// Iterate over the visible rows of this chunk.
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Option<ScalarRefImpl<'_>>>> + '_ {
+        self.visible_indices().map(|i| self.row(i))
+    }
+}
+
+/// Keep the rows of `array` whose bit is set in `visibility`.
+pub(crate) fn compact_array(array: &ArrayImpl, visibility: &BitVec) -> ArrayImpl {
+    let indices: Vec<_> = visibility.iter_ones().collect();
+    kernel::gather(array, &indices)
+}
+
+/// Check that `array` holds values of `data_type`, including its type parameters.
+///
+/// The time zone of a timestamp and the width of a char are not part of the array. Type
+/// parameters can't be checked on arrays without values that were built without them: the
+/// scale of a decimal, the element type of a list or the fields of a struct.
+fn check_type(array: &ArrayImpl, data_type: &DataType) -> Result<()> {
+    let no_values = array.null_count() == array.len();
+    match (data_type, array) {
+        (DataType::SmallInt, ArrayImpl::Int16(_))
+        | (DataType::Integer, ArrayImpl::Int32(_))
+        | (DataType::BigInt, ArrayImpl::Int64(_))
+        | (DataType::Varchar | DataType::Char { .. }, ArrayImpl::String(_))
+        | (DataType::Bytea, ArrayImpl::Bytes(_))
+        | (DataType::Boolean, ArrayImpl::Boolean(_))
+        | (DataType::Real, ArrayImpl::Float32(_))
+        | (DataType::Double, ArrayImpl::Float64(_))
+        | (DataType::Date, ArrayImpl::Date(_))
+        | (DataType::Time, ArrayImpl::Time(_))
+        | (DataType::Timestamp { .. }, ArrayImpl::Timestamp(_))
+        | (DataType::Interval, ArrayImpl::Interval(_)) => Ok(()),
+        (DataType::Decimal { scale, .. }, ArrayImpl::Decimal(array)) => {
+            if *scale == array.scale() as u16 || no_values {
+                Ok(())
+            } else {
+                Err(ArrayError::InvalidArgument(format!(
+                    "expected decimals with scale {}, found scale {}",
+                    scale,
+                    array.scale()
+                )))
+            }
+        }
+        (DataType::List { element }, ArrayImpl::List(array)) => match array.child() {
+            Some(child) => check_type(child, element).map_err(|err| err.context("list element")),
+            None => Ok(()),
+        },
+        (DataType::Struct { fields }, ArrayImpl::Struct(array)) => {
+            let names = array.field_names();
+            if names.is_empty() && no_values {
+                return Ok(());
+            }
+            if names.len() != fields.len() || names.iter().zip(fields).any(|(a, (b, _))| a != b) {
+                return Err(ArrayError::InvalidArgument(format!(
+                    "expected struct fields {:?}, found {:?}",
+                    fields.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+                    names
+                )));
+            }
+            for (index, (name, data_type)) in fields.iter().enumerate() {
+                let child = array.field(index).expect("field count checked above");
+                check_type(child, data_type)
+                    .map_err(|err| err.context(format!("field {}", name)))?;
+            }
+            Ok(())
+        }
+        _ => Err(ArrayError::TypeMismatch {
+            expected: array_type_name(data_type),
+            actual: array.type_name(),
+        }),
+    }
+}
+
+/// The name of the array type holding values of `data_type`, as given by
+/// [`ArrayImpl::type_name`].
+fn array_type_name(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::SmallInt => "Int16Array",
+        DataType::Integer => "Int32Array",
+        DataType::BigInt => "Int64Array",
+        DataType::Varchar | DataType::Char { .. } => "StringArray",
+        DataType::Bytea => "BytesArray",
+        DataType::Boolean => "BooleanArray",
+        DataType::Real => "Float32Array",
+        DataType::Double => "Float64Array",
+        DataType::Decimal { .. } => "DecimalArray",
+        DataType::Date => "DateArray",
+        DataType::Time => "TimeArray",
+        DataType::Timestamp { .. } => "TimestampArray",
+        DataType::Interval => "IntervalArray",
+        DataType::List { .. } => "ListArray",
+        DataType::Struct { .. } => "StructArray",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        array_impl::{ListArrayBuilder, ListValue, StringArray, StructArray},
+        prelude::{DecimalArray, Int32Array},
+        ArrayBuilder, Field, Scalar, Schema,
+    };
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Integer, false),
+            Field::new("name", DataType::Varchar, true),
+        ]))
+    }
+
+    fn ids(values: &[Option<i32>]) -> ArrayImpl {
+        Int32Array::from_slice(values).into()
+    }
+
+    fn mask(values: &[Option<bool>]) -> BooleanArray {
+        BooleanArray::from_slice(values)
+    }
+
+    #[test]
+    fn test_data_chunk() {
+        let chunk = DataChunk::try_new(
+            schema(),
+            vec![
+                ids(&[Some(1), Some(2)]),
+                StringArray::from_slice(&[Some("a"), None]).into(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(chunk.cardinality(), 2);
+        assert_eq!(chunk.schema().index_of("name"), Some(1));
+        assert_eq!(
+            chunk.row(0),
+            vec![
+                Some(ScalarRefImpl::Int32(1)),
+                Some(ScalarRefImpl::String("a"))
+            ]
+        );
+        assert_eq!(
+            chunk.rows().collect::<Vec<_>>(),
+            vec![
+                vec![
+                    Some(ScalarRefImpl::Int32(1)),
+                    Some(ScalarRefImpl::String("a"))
+                ],
+                vec![Some(ScalarRefImpl::Int32(2)), None],
+            ]
+        );
+
+        let projected = chunk.project(&[1]).unwrap();
+        assert_eq!(projected.schema().field(0).name, "name");
+        assert_eq!(projected.column(0).unwrap().get(1), None);
+        assert_eq!(projected.cardinality(), 2);
+        assert!(chunk.project(&[0, 2]).is_err());
+        assert!(chunk.column(2).is_err());
+    }
+
+    #[test]
+    fn test_data_chunk_errors() {
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("a")]).into();
+
+        let err = DataChunk::try_new(schema(), vec![ids(&[Some(1)])]).unwrap_err();
+        assert!(matches!(err, ArrayError::ArityMismatch { .. }));
+
+        let err = DataChunk::try_new(schema(), vec![ids(&[Some(1), Some(2)]), strings.clone()])
+            .unwrap_err();
+        assert!(matches!(err, ArrayError::LengthMismatch { .. }));
+
+        let err = DataChunk::try_new(schema(), vec![strings.clone(), strings.clone()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column id: type mismatch: expected Int32Array, found StringArray"
+        );
+
+        let err = DataChunk::try_new(schema(), vec![ids(&[None]), strings]).unwrap_err();
+        assert!(matches!(err, ArrayError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn test_nested_column_types() {
+        let decimals: ArrayImpl = DecimalArray::from_slice(&[Some("1.50".parse().unwrap())]).into();
+        let decimal = |scale| DataType::Decimal {
+            scale,
+            precision: 10,
+        };
+        let schema = |data_type| Arc::new(Schema::new(vec![Field::new("c", data_type, true)]));
+        assert!(DataChunk::try_new(schema(decimal(2)), vec![decimals.clone()]).is_ok());
+        let err = DataChunk::try_new(schema(decimal(3)), vec![decimals]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column c: invalid argument: expected decimals with scale 3, found scale 2"
+        );
+
+        let list = |element| DataType::List {
+            element: Box::new(element),
+        };
+        let mut builder = ListArrayBuilder::with_element(1, &DataType::Integer).unwrap();
+        builder.push(Some(ListValue::new(ids(&[Some(1)])).as_scalar_ref()));
+        let lists: ArrayImpl = builder.finish().into();
+        assert!(DataChunk::try_new(schema(list(DataType::Integer)), vec![lists.clone()]).is_ok());
+        let err = DataChunk::try_new(schema(list(DataType::BigInt)), vec![lists]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column c: list element: type mismatch: expected Int64Array, found Int32Array"
+        );
+
+        let structs: ArrayImpl = StructArray::new(vec![("id".to_string(), ids(&[Some(1)]))]).into();
+        let fields = |name: &str, data_type| DataType::Struct {
+            fields: vec![(name.to_string(), data_type)],
+        };
+        assert!(DataChunk::try_new(
+            schema(fields("id", DataType::Integer)),
+            vec![structs.clone()]
+        )
+        .is_ok());
+        let err = DataChunk::try_new(
+            schema(fields("id", DataType::Varchar)),
+            vec![structs.clone()],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column c: field id: type mismatch: expected StringArray, found Int32Array"
+        );
+        assert!(
+            DataChunk::try_new(schema(fields("key", DataType::Integer)), vec![structs]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_visibility() {
+        let chunk = DataChunk::try_new(
+            schema(),
+            vec![
+                ids(&[Some(1), Some(2), Some(3)]),
+                StringArray::from_slice(&[Some("a"), None, Some("c")]).into(),
+            ],
+        )
+        .unwrap();
+        let chunk = chunk
+            .filter(&mask(&[Some(true), Some(true), None]))
+            .unwrap();
+        let chunk = chunk
+            .filter(&mask(&[Some(false), Some(true), Some(true)]))
+            .unwrap();
+        assert_eq!(chunk.cardinality(), 1);
+        assert_eq!(chunk.capacity(), 3);
+        assert_eq!(chunk.column(0).unwrap().len(), 3);
+        assert_eq!(chunk.visible_indices().collect::<Vec<_>>(), [1]);
+        assert!(!chunk.is_visible(3));
+        assert_eq!(
+            chunk.rows().collect::<Vec<_>>(),
+            [vec![Some(ScalarRefImpl::Int32(2)), None]]
+        );
+        assert_eq!(chunk.project(&[0]).unwrap().cardinality(), 1);
+
+        let chunk = chunk.compact();
+        assert!(chunk.visibility().is_none());
+        assert!(!chunk.is_visible(1));
+        assert_eq!(chunk.capacity(), 1);
+        assert_eq!(
+            chunk.column(0).unwrap().get(0),
+            Some(ScalarRefImpl::Int32(2))
+        );
+
+        assert!(chunk.filter(&mask(&[])).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 /// Encapsules all supported (logical) data types in the system.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DataType {
     /// Corresponding to Int16 physical type
     SmallInt,
@@ -11,12 +11,27 @@ pub enum DataType {
     Varchar,
     /// Corresponding to String physical type
     Char { width: u16 },
+    /// Corresponding to Bytes physical type
+    Bytea,
     /// Corresponding to Bool physical type
     Boolean,
     /// Corresponding to Float32 physical type
     Real,
     /// Corresponding to Float64 physical type
     Double,
-    /// Corresponding to Decimal physical type
+    /// Corresponding to Decimal physical type. Casts and builders created from the type reject
+    /// values with more than `precision` digits once rounded to `scale`.
     Decimal { scale: u16, precision: u16 },
+    /// Corresponding to Date physical type
+    Date,
+    /// Corresponding to Time physical type
+    Time,
+    /// Corresponding to Timestamp physical type, optionally tagged with a time zone
+    Timestamp { timezone: Option<String> },
+    /// Corresponding to Interval physical type
+    Interval,
+    /// Corresponding to List physical type
+    List { element: Box<DataType> },
+    /// Corresponding to Struct physical type, with the name and type of each field
+    Struct { fields: Vec<(String, DataType)> },
 }
@@ -0,0 +1,395 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+const DAYS_PER_MONTH: i64 = 30;
+
+/// A calendar date, stored as the number of days since `1970-01-01`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(i32);
+
+/// A time of day, stored as microseconds since midnight.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time(i64);
+
+/// A point in time, stored as microseconds since `1970-01-01 00:00:00` UTC.
+///
+/// The time zone of a timestamp column only lives in its
+/// [`DataType`](crate::DataType); the values themselves are always UTC.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+/// A span of time made of months, days and microseconds.
+///
+/// The three parts are kept apart because a month has no fixed number of days.
+/// For comparison, a month counts as 30 days, so `1 mon` equals `30 days`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Date {
+    /// The earliest date, the day of the earliest [`Timestamp`].
+    pub const MIN: Self = Self(i64::MIN.div_euclid(MICROS_PER_DAY) as i32);
+    /// The latest date, the day of the latest [`Timestamp`].
+    pub const MAX: Self = Self(i64::MAX.div_euclid(MICROS_PER_DAY) as i32);
+
+    /// Returns `None` if the date is out of the range of [`Date::MIN`] to [`Date::MAX`].
+    pub fn from_days(days: i32) -> Option<Self> {
+        (Self::MIN.0..=Self::MAX.0)
+            .contains(&days)
+            .then_some(Self(days))
+    }
+
+    /// Build a date from the proleptic Gregorian calendar.
+    /// Returns `None` for an invalid month or day, or a date out of range.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Self::from_days(days_from_civil(year, month, day).try_into().ok()?)
+    }
+
+    pub fn days(&self) -> i32 {
+        self.0
+    }
+
+    /// Returns `(year, month, day)`.
+    pub fn to_ymd(&self) -> (i32, u32, u32) {
+        civil_from_days(self.0)
+    }
+}
+
+impl Time {
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        (0..MICROS_PER_DAY)
+            .contains(&micros)
+            .then_some(Self(micros))
+    }
+
+    pub fn from_hms_micro(hour: u32, minute: u32, second: u32, micro: u32) -> Option<Self> {
+        if hour >= 24 || minute >= 60 || second >= 60 || micro as i64 >= MICROS_PER_SECOND {
+            return None;
+        }
+        let seconds = (hour * 3600 + minute * 60 + second) as i64;
+        Some(Self(seconds * MICROS_PER_SECOND + micro as i64))
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns `(hour, minute, second, microsecond)`.
+    pub fn to_hms_micro(&self) -> (u32, u32, u32, u32) {
+        let seconds = (self.0 / MICROS_PER_SECOND) as u32;
+        let micro = (self.0 % MICROS_PER_SECOND) as u32;
+        (seconds / 3600, seconds / 60 % 60, seconds % 60, micro)
+    }
+}
+
+impl Timestamp {
+    pub fn from_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    /// Returns `None` if the timestamp is out of range.
+    pub fn new(date: Date, time: Time) -> Option<Self> {
+        let micros = (date.0 as i64).checked_mul(MICROS_PER_DAY)?;
+        Some(Self(micros.checked_add(time.0)?))
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+
+    pub fn date(&self) -> Date {
+        Date(self.0.div_euclid(MICROS_PER_DAY) as i32)
+    }
+
+    pub fn time(&self) -> Time {
+        Time(self.0.rem_euclid(MICROS_PER_DAY))
+    }
+}
+
+impl From<Date> for Timestamp {
+    /// The midnight of `date`. The midnight of [`Date::MIN`] is before the earliest timestamp,
+    /// so it saturates to that timestamp.
+    fn from(date: Date) -> Self {
+        Self((date.0 as i64).saturating_mul(MICROS_PER_DAY))
+    }
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Self {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    /// The length of this interval in microseconds, with a month counted as 30 days.
+    fn total_micros(&self) -> i128 {
+        let days = self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128;
+        days * MICROS_PER_DAY as i128 + self.micros as i128
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_micros() == other.total_micros()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_micros().cmp(&other.total_micros())
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since `1970-01-01` of a Gregorian date, after Howard Hinnant's `days_from_civil`.
+///
+/// Computed in `i64`, which can't overflow for any `i32` year.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 {
+        year as i64 - 1
+    } else {
+        year as i64
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400) as u32;
+    let month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era as i64 - 719468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i32) -> (i32, u32, u32) {
+    let days = days as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097) as u32;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era as i64 + era * 400 + (month <= 2) as i64;
+    (year as i32, month, day)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.to_ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (hour, minute, second, micro) = self.to_hms_micro();
+        write!(f, "{:02}:{:02}:{:02}", hour, minute, second)?;
+        if micro != 0 {
+            write!(f, ".{:06}", micro)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.date(), self.time())
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.micros < 0 { "-" } else { "" };
+        let micros = self.micros.unsigned_abs();
+        let day = MICROS_PER_DAY as u64;
+        let time = Time((micros % day) as i64);
+        let whole_days = (micros / day) as i64;
+        let days = match self.micros < 0 {
+            true => self.days as i64 - whole_days,
+            false => self.days as i64 + whole_days,
+        };
+        write!(f, "{} mons {} days {}{}", self.months, days, sign, time)
+    }
+}
+
+/// An error returned when parsing a date, time or timestamp from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDateTimeError;
+
+impl fmt::Display for ParseDateTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date/time literal")
+    }
+}
+
+impl std::error::Error for ParseDateTimeError {}
+
+fn parse_num<T: FromStr>(s: &str) -> Result<T, ParseDateTimeError> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(ParseDateTimeError);
+    }
+    s.parse().map_err(|_| ParseDateTimeError)
+}
+
+impl FromStr for Date {
+    type Err = ParseDateTimeError;
+
+    /// Parse an ISO 8601 date such as `2022-03-01`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or(ParseDateTimeError);
+        let (year, month, day) = (next()?, next()?, next()?);
+        Date::from_ymd(parse_num(year)?, parse_num(month)?, parse_num(day)?)
+            .ok_or(ParseDateTimeError)
+    }
+}
+
+impl FromStr for Time {
+    type Err = ParseDateTimeError;
+
+    /// Parse a time such as `12:30:00` or `12:30:00.250`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hms, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        let mut parts = hms.splitn(3, ':');
+        let mut next = || parts.next().ok_or(ParseDateTimeError);
+        let (hour, minute, second) = (next()?, next()?, next()?);
+        let micro = match fraction.len() {
+            0 => 0,
+            1..=6 => parse_num::<u32>(fraction)? * 10u32.pow(6 - fraction.len() as u32),
+            _ => return Err(ParseDateTimeError),
+        };
+        Time::from_hms_micro(
+            parse_num(hour)?,
+            parse_num(minute)?,
+            parse_num(second)?,
+            micro,
+        )
+        .ok_or(ParseDateTimeError)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseDateTimeError;
+
+    /// Parse a timestamp such as `2022-03-01 12:30:00`. A date alone means midnight.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.split_once([' ', 'T']) {
+            Some((date, time)) => {
+                Timestamp::new(date.parse()?, time.parse()?).ok_or(ParseDateTimeError)
+            }
+            None => Ok(Timestamp::from(s.parse::<Date>()?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_roundtrip() {
+        assert_eq!(Date::from_ymd(1970, 1, 1).unwrap().days(), 0);
+        assert_eq!(Date::from_ymd(1969, 12, 31).unwrap().days(), -1);
+        assert_eq!(Date::from_ymd(2000, 3, 1).unwrap().days(), 11017);
+        for days in [-800_000, -1, 0, 59, 60, 11016, 800_000] {
+            let (year, month, day) = Date::from_days(days).unwrap().to_ymd();
+            assert_eq!(Date::from_ymd(year, month, day).unwrap().days(), days);
+        }
+        assert!(Date::from_ymd(2023, 2, 29).is_none());
+        assert!(Date::from_ymd(2024, 2, 29).is_some());
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let date: Date = "2022-03-01".parse().unwrap();
+        assert_eq!(date.to_string(), "2022-03-01");
+        assert!("2022-13-01".parse::<Date>().is_err());
+
+        let time: Time = "08:05:03.25".parse().unwrap();
+        assert_eq!(time.to_string(), "08:05:03.250000");
+        assert!("24:00:00".parse::<Time>().is_err());
+
+        let ts: Timestamp = "1969-12-31 23:59:59".parse().unwrap();
+        assert_eq!(ts.micros(), -MICROS_PER_SECOND);
+        assert_eq!(ts.to_string(), "1969-12-31 23:59:59");
+        assert_eq!(
+            "2022-03-01".parse::<Timestamp>().unwrap(),
+            Timestamp::from(date)
+        );
+
+        assert_eq!(
+            Interval::new(1, 2, -3 * MICROS_PER_SECOND).to_string(),
+            "1 mons 2 days -00:00:03"
+        );
+        assert_eq!(
+            Interval::new(0, 0, i64::MIN).to_string(),
+            "0 mons -106751991 days -04:00:54.775808"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert!("9999999-01-01".parse::<Date>().is_err());
+        assert!("2147483647-12-31 00:00:00".parse::<Timestamp>().is_err());
+        assert!(Date::from_days(i32::MAX).is_none());
+        assert!(Date::from_days(Date::MIN.days() - 1).is_none());
+
+        let max = Timestamp::from_micros(i64::MAX);
+        assert_eq!(max.date(), Date::MAX);
+        assert_eq!(Timestamp::new(max.date(), max.time()), Some(max));
+        assert_eq!(Date::MAX.to_string(), "294247-01-10");
+        assert_eq!(Date::MIN.to_string(), "-290308-12-21");
+        assert_eq!(Timestamp::from_micros(i64::MIN).date(), Date::MIN);
+        assert_eq!(Timestamp::new(Date::MIN, Time::default()), None);
+        assert_eq!(Timestamp::from(Date::MIN), Timestamp::from_micros(i64::MIN));
+    }
+
+    #[test]
+    fn test_interval_cmp() {
+        assert_eq!(Interval::new(1, 0, 0), Interval::new(0, 30, 0));
+        assert!(Interval::new(0, 1, 0) > Interval::new(0, 0, MICROS_PER_DAY - 1));
+        assert!(Interval::new(-1, 0, 0) < Interval::new(0, -29, 0));
+    }
+}
@@ -0,0 +1,372 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// The maximum number of decimal digits a [`Decimal`] can hold.
+pub const MAX_PRECISION: u8 = 38;
+
+/// The maximum number of digits after the decimal point.
+pub const MAX_SCALE: u8 = MAX_PRECISION;
+
+/// The minimum number of digits after the decimal point of a quotient.
+pub(crate) const MIN_DIV_SCALE: u8 = 6;
+
+/// A fixed-point decimal number.
+///
+/// The value is `mantissa * 10^-scale`, so `Decimal::new(12345, 2)` is `123.45`.
+/// Two decimals with different scales compare equal if they represent the same value.
+///
+/// A [`DecimalArray`](crate::prelude::DecimalArray) stores all its values with the same scale.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u8,
+}
+
+impl Decimal {
+    /// Create a decimal from its raw mantissa and scale.
+    /// Returns `None` if `scale` is larger than [`MAX_SCALE`].
+    pub fn new(mantissa: i128, scale: u8) -> Option<Self> {
+        (scale <= MAX_SCALE).then_some(Self { mantissa, scale })
+    }
+
+    /// Create a decimal from a mantissa and a scale known to be in range.
+    pub(crate) fn from_parts(mantissa: i128, scale: u8) -> Self {
+        debug_assert!(scale <= MAX_SCALE);
+        Self { mantissa, scale }
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Number of significant decimal digits in the mantissa.
+    pub fn precision(&self) -> u8 {
+        let mut digits = 1;
+        let mut value = self.mantissa.unsigned_abs() / 10;
+        while value != 0 {
+            digits += 1;
+            value /= 10;
+        }
+        digits
+    }
+
+    /// Convert to another scale, rounding half away from zero when digits are dropped.
+    /// Returns `None` if the result does not fit.
+    pub fn rescale(&self, scale: u8) -> Option<Self> {
+        if scale > MAX_SCALE {
+            return None;
+        }
+        let mantissa = match scale.cmp(&self.scale) {
+            Ordering::Equal => self.mantissa,
+            Ordering::Greater => self.mantissa.checked_mul(pow10(scale - self.scale))?,
+            Ordering::Less => {
+                let divisor = pow10(self.scale - scale);
+                let quotient = self.mantissa / divisor;
+                let remainder = self.mantissa % divisor;
+                if remainder.unsigned_abs() * 2 >= divisor.unsigned_abs() {
+                    quotient + self.mantissa.signum()
+                } else {
+                    quotient
+                }
+            }
+        };
+        Some(Self { mantissa, scale })
+    }
+
+    /// The mantissas of `self` and `other` at the larger of their scales, and that scale.
+    fn align(self, other: Self) -> Option<(i128, i128, u8)> {
+        let scale = self.scale.max(other.scale);
+        let lhs = self.rescale(scale)?;
+        let rhs = other.rescale(scale)?;
+        Some((lhs.mantissa, rhs.mantissa, scale))
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        Some(Self::from_parts(lhs.checked_add(rhs)?, scale))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        Some(Self::from_parts(lhs.checked_sub(rhs)?, scale))
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let scale = self.scale.checked_add(other.scale)?;
+        Self::new(self.mantissa.checked_mul(other.mantissa)?, scale)
+    }
+
+    /// Divide by a non-zero `other`, rounding half away from zero.
+    ///
+    /// The quotient has the larger scale of the operands, and at least 6 digits after the point.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.mantissa == 0 {
+            return None;
+        }
+        let scale = self.scale.max(other.scale).max(MIN_DIV_SCALE);
+        // The quotient of the mantissas is scaled by `10^(self.scale - other.scale)`, so
+        // `exp` more digits are needed, computed one at a time by long division.
+        let exp = scale + other.scale - self.scale;
+        let divisor = other.mantissa.unsigned_abs();
+        let mut quotient = self.mantissa.unsigned_abs() / divisor;
+        let mut remainder = self.mantissa.unsigned_abs() % divisor;
+        for _ in 0..exp {
+            let dividend = remainder.checked_mul(10)?;
+            quotient = quotient.checked_mul(10)?.checked_add(dividend / divisor)?;
+            remainder = dividend % divisor;
+        }
+        if remainder >= divisor - remainder {
+            quotient = quotient.checked_add(1)?;
+        }
+        let mantissa = if (self.mantissa < 0) != (other.mantissa < 0) {
+            0i128.checked_sub_unsigned(quotient)?
+        } else {
+            i128::try_from(quotient).ok()?
+        };
+        Some(Self::from_parts(mantissa, scale))
+    }
+
+    /// The remainder of dividing by a non-zero `other`, with the sign of `self`.
+    pub fn checked_rem(self, other: Self) -> Option<Self> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        // `i128::MIN % -1` overflows, but the remainder is well defined.
+        let mantissa = if rhs == -1 { 0 } else { lhs.checked_rem(rhs)? };
+        Some(Self::from_parts(mantissa, scale))
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self::from_parts(self.mantissa.checked_neg()?, self.scale))
+    }
+
+    pub fn checked_abs(self) -> Option<Self> {
+        Some(Self::from_parts(self.mantissa.checked_abs()?, self.scale))
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Split into the integral part and the fractional part scaled to `10^scale`.
+    fn split(&self, scale: u8) -> (i128, i128) {
+        let divisor = pow10(self.scale);
+        let fraction = self.mantissa % divisor * pow10(scale - self.scale);
+        (self.mantissa / divisor, fraction)
+    }
+}
+
+fn pow10(exp: u8) -> i128 {
+    10i128.pow(exp as u32)
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.scale == other.scale {
+            return self.mantissa.cmp(&other.mantissa);
+        }
+        // Aligning the mantissas may overflow, so compare the integral parts first.
+        // Both fractional parts are below `10^MAX_SCALE` and can't overflow.
+        let scale = self.scale.max(other.scale);
+        self.split(scale).cmp(&other.split(scale))
+    }
+}
+
+macro_rules! impl_decimal_from_int {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Decimal {
+                fn from(value: $ty) -> Self {
+                    Self::from_parts(value as i128, 0)
+                }
+            }
+        )*
+    };
+}
+
+impl_decimal_from_int!(i16, i32, i64);
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integral, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integral, fraction)
+    }
+}
+
+/// An error returned when parsing a [`Decimal`] from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError;
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal literal")
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    /// Parse a plain decimal literal such as `-12.340`. The scale is the number of
+    /// digits after the point.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integral, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integral.is_empty() && fraction.is_empty() {
+            return Err(ParseDecimalError);
+        }
+        if fraction.len() > MAX_SCALE as usize {
+            return Err(ParseDecimalError);
+        }
+
+        // Accumulate with the final sign, so that `i128::MIN` can be parsed.
+        let sign = if negative { -1 } else { 1 };
+        let mut mantissa: i128 = 0;
+        for c in integral.bytes().chain(fraction.bytes()) {
+            if !c.is_ascii_digit() {
+                return Err(ParseDecimalError);
+            }
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(sign * (c - b'0') as i128))
+                .ok_or(ParseDecimalError)?;
+        }
+        Ok(Self::from_parts(mantissa, fraction.len() as u8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dispatch::{ArrayImpl, ScalarRefImpl},
+        prelude::{DecimalArray, DecimalArrayBuilder},
+        Array, ArrayBuilder,
+    };
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in ["0", "123.45", "-0.001", "100", "-42.500"] {
+            assert_eq!(dec(s).to_string(), s);
+        }
+        assert_eq!(dec("+.5").to_string(), "0.5");
+        assert_eq!(dec("-12.340").scale(), 3);
+        assert!("".parse::<Decimal>().is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("abc".parse::<Decimal>().is_err());
+
+        let min = i128::MIN.to_string();
+        assert_eq!(dec(&min).mantissa(), i128::MIN);
+        assert!(format!("-{}", i128::MAX as u128 + 2)
+            .parse::<Decimal>()
+            .is_err());
+        assert!(Decimal::new(1, MAX_SCALE + 1).is_none());
+    }
+
+    #[test]
+    fn test_compare_across_scales() {
+        assert_eq!(dec("1.50"), dec("1.5"));
+        assert!(dec("1.05") < dec("1.5"));
+        assert!(dec("-1.5") < dec("-1.05"));
+        assert!(Decimal::new(i128::MAX, 0).unwrap() > Decimal::new(1, 38).unwrap());
+    }
+
+    #[test]
+    fn test_rescale() {
+        assert_eq!(dec("1.25").rescale(1).unwrap().to_string(), "1.3");
+        assert_eq!(dec("-1.25").rescale(1).unwrap().to_string(), "-1.3");
+        assert_eq!(dec("1.24").rescale(1).unwrap().to_string(), "1.2");
+        assert_eq!(dec("1.2").rescale(3).unwrap().to_string(), "1.200");
+        assert!(Decimal::new(i128::MAX, 0).unwrap().rescale(1).is_none());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(dec("1.5").checked_add(dec("0.25")), Some(dec("1.75")));
+        assert_eq!(dec("1.5").checked_sub(dec("2")), Some(dec("-0.5")));
+        assert_eq!(dec("1.5").checked_mul(dec("-0.2")), Some(dec("-0.3")));
+        assert!(Decimal::new(i128::MAX, 0)
+            .unwrap()
+            .checked_add(dec("1"))
+            .is_none());
+
+        let min = Decimal::new(i128::MIN, 0).unwrap();
+        assert_eq!(min.checked_sub(dec("-1")), Decimal::new(i128::MIN + 1, 0));
+        assert!(min.checked_sub(dec("1")).is_none());
+        assert!(dec("0.1")
+            .checked_mul(Decimal::new(1, MAX_SCALE).unwrap())
+            .is_none());
+
+        assert_eq!(
+            dec("1").checked_div(dec("3")).unwrap().to_string(),
+            "0.333333"
+        );
+        assert_eq!(
+            dec("-2").checked_div(dec("3")).unwrap().to_string(),
+            "-0.666667"
+        );
+        assert_eq!(dec("1.5").checked_div(dec("-0.25")), Some(dec("-6")));
+        assert_eq!(
+            dec("1").checked_div(dec("0.0000001")).unwrap().to_string(),
+            "10000000.0000000"
+        );
+        assert!(dec("1").checked_div(dec("0")).is_none());
+        assert!(min.checked_div(dec("0.1")).is_none());
+
+        assert_eq!(dec("7.5").checked_rem(dec("2")), Some(dec("1.5")));
+        assert_eq!(dec("-7.5").checked_rem(dec("2")), Some(dec("-1.5")));
+        assert_eq!(min.checked_rem(dec("-1")), Some(dec("0")));
+        assert!(dec("1").checked_rem(dec("0")).is_none());
+    }
+
+    #[test]
+    fn test_decimal_array() {
+        let mut builder = DecimalArrayBuilder::with_capacity(3);
+        builder.push(Some(dec("9.99")));
+        builder.push(None);
+        builder.push(Some(Decimal::from(7i32)));
+        let array: ArrayImpl = builder.finish().into();
+
+        assert_eq!(array.get(0), Some(ScalarRefImpl::Decimal(dec("9.99"))));
+        assert_eq!(array.get(1), None);
+
+        let array: DecimalArray = array.try_into().unwrap();
+        // Every value has the scale of the array.
+        assert_eq!(
+            array.get(2).map(|d| d.to_string()),
+            Some("7.00".to_string())
+        );
+    }
+}
@@ -1,21 +1,49 @@
 #![allow(unused)]
 
 use crate::prelude::*;
-use crate::{array_impl::StringArray, Array, Scalar, ScalarRef};
+use crate::{
+    array_impl::{
+        BytesArray, BytesArrayBuilder, ListArray, ListArrayBuilder, ListRef, ListValue,
+        StringArray, StringArrayBuilder, StructArray, StructArrayBuilder, StructRef, StructValue,
+    },
+    decimal::{MAX_PRECISION, MAX_SCALE},
+    Array, ArrayBuilder, ArrayError, Bitmap, DataType, Date, Decimal, Interval, Result, Scalar,
+    ScalarRef, Time, Timestamp,
+};
 
 macro_rules! impl_scalar_dispatch {
     ($( { $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty } ),*) => {
-        #[derive(Debug, PartialEq)]
+        #[derive(Debug, PartialEq, Clone)]
         pub enum ScalarImpl {
             $( $Abc($Owned) ),*
         }
 
         impl ScalarImpl {
-            fn as_scalar_ref(&self) -> ScalarRefImpl<'_> {
+            pub fn as_scalar_ref(&self) -> ScalarRefImpl<'_> {
                 match self {
                     $( Self::$Abc(scalar_ref) => scalar_ref.as_scalar_ref().into() ),*
                 }
             }
+
+            /// An array with this value as its only row.
+            pub fn to_array(&self) -> ArrayImpl {
+                match self {
+                    $(
+                        Self::$Abc(scalar) => {
+                            let mut builder = <$AbcArrayBuilder>::with_capacity(1);
+                            builder.push(Some(scalar.as_scalar_ref()));
+                            builder.finish().into()
+                        }
+                    ),*
+                }
+            }
+
+            /// The name of the physical type of this value.
+            pub fn type_name(&self) -> &'static str {
+                match self {
+                    $( Self::$Abc(_) => stringify!($Abc) ),*
+                }
+            }
         }
 
         $(
@@ -26,12 +54,15 @@ macro_rules! impl_scalar_dispatch {
             }
 
             impl TryFrom<ScalarImpl> for $Owned {
-                type Error = ();
+                type Error = ArrayError;
 
                 fn try_from(scalar: ScalarImpl) -> Result<Self, Self::Error> {
                     match scalar {
                         ScalarImpl::$Abc(scalar) => Ok(scalar),
-                        _ => Err(())
+                        other => Err(ArrayError::TypeMismatch {
+                            expected: stringify!($Abc),
+                            actual: other.type_name(),
+                        }),
                     }
                 }
             }
@@ -41,17 +72,24 @@ macro_rules! impl_scalar_dispatch {
 
 macro_rules! impl_scalar_ref_dispatch {
     ($( { $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty } ),*) => {
-        #[derive(Debug, PartialEq)]
+        #[derive(Debug, PartialEq, Clone, Copy)]
         pub enum ScalarRefImpl<'a> {
             $( $Abc($Ref) ),*
         }
 
         impl<'a> ScalarRefImpl<'a> {
-            fn to_scalar_owned(&self) -> ScalarImpl {
+            fn to_scalar_owned(self) -> ScalarImpl {
                 match self {
                     $( ScalarRefImpl::$Abc(scalar_ref) => scalar_ref.to_scalar_owned().into() ),*
                 }
             }
+
+            /// The name of the physical type of this value.
+            pub fn type_name(&self) -> &'static str {
+                match self {
+                    $( Self::$Abc(_) => stringify!($Abc) ),*
+                }
+            }
         }
 
         $(
@@ -62,12 +100,15 @@ macro_rules! impl_scalar_ref_dispatch {
             }
 
             impl<'a> TryFrom<ScalarRefImpl<'a>> for $Ref {
-                type Error = ();
+                type Error = ArrayError;
 
                 fn try_from(ref_impl: ScalarRefImpl<'a>) -> Result<Self, Self::Error> {
                     match ref_impl {
                         ScalarRefImpl::$Abc(scalar_ref) => Ok(scalar_ref),
-                        _ => Err(())
+                        other => Err(ArrayError::TypeMismatch {
+                            expected: stringify!($Abc),
+                            actual: other.type_name(),
+                        }),
                     }
                 }
             }
@@ -77,6 +118,7 @@ macro_rules! impl_scalar_ref_dispatch {
 
 macro_rules! impl_array_dispatch {
     ($( { $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty } ),*) => {
+        #[derive(Debug, Clone)]
         pub enum ArrayImpl {
             $( $Abc($AbcArray) ),*
         }
@@ -88,11 +130,58 @@ macro_rules! impl_array_dispatch {
                 }
             }
 
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// The name of the physical type of this array.
+            pub fn type_name(&self) -> &'static str {
+                match self {
+                    $(ArrayImpl::$Abc(_) => stringify!($AbcArray)),*
+                }
+            }
+
             pub fn get(&self, index: usize) -> Option<ScalarRefImpl<'_>> {
                 match self {
                     $(ArrayImpl::$Abc(array) => array.get(index).map(ScalarRefImpl::$Abc)),*
                 }
             }
+
+            /// The validity of each value.
+            pub fn validity(&self) -> &Bitmap {
+                match self {
+                    $(ArrayImpl::$Abc(array) => array.validity()),*
+                }
+            }
+
+            /// Number of null values.
+            pub fn null_count(&self) -> usize {
+                self.validity().null_count()
+            }
+
+            /// Whether the value at `index` is null.
+            ///
+            /// Panics if the index is out of bounds.
+            pub fn is_null(&self, index: usize) -> bool {
+                !self.validity().is_valid(index)
+            }
+
+            /// Get `len` rows starting at `offset`, without copying them.
+            ///
+            /// Panics if the rows are out of bounds.
+            pub fn slice(&self, offset: usize, len: usize) -> Self {
+                match self {
+                    $(ArrayImpl::$Abc(array) => array.slice(offset, len).into()),*
+                }
+            }
+
+            /// Create an empty builder for arrays of the same type as this one, including the
+            /// scale of decimals and the types of nested values.
+            pub fn new_builder(&self, capacity: usize) -> ArrayBuilderImpl {
+                match self {
+                    $(ArrayImpl::$Abc(array) => ArrayBuilderImpl::$Abc(array.new_builder(capacity))),*
+                }
+            }
         }
 
         $(
@@ -103,23 +192,29 @@ macro_rules! impl_array_dispatch {
             }
 
             impl TryFrom<ArrayImpl> for $AbcArray {
-                type Error = ();
+                type Error = ArrayError;
 
                 fn try_from(value: ArrayImpl) -> Result<Self, Self::Error> {
                     match value {
                         ArrayImpl::$Abc(array) => Ok(array),
-                        _ => Err(()),
+                        other => Err(ArrayError::TypeMismatch {
+                            expected: stringify!($AbcArray),
+                            actual: other.type_name(),
+                        }),
                     }
                 }
             }
 
             impl<'a> TryFrom<&'a ArrayImpl> for &'a $AbcArray {
-                type Error = ();
+                type Error = ArrayError;
 
                 fn try_from(array: &'a ArrayImpl) -> Result<&'a $AbcArray, Self::Error> {
                     match array {
                         ArrayImpl::$Abc(array) => Ok(array),
-                        _ => Err(()),
+                        other => Err(ArrayError::TypeMismatch {
+                            expected: stringify!($AbcArray),
+                            actual: other.type_name(),
+                        }),
                     }
                 }
             }
@@ -127,16 +222,137 @@ macro_rules! impl_array_dispatch {
     };
 }
 
+macro_rules! impl_array_builder_dispatch {
+    ($( { $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty } ),*) => {
+        /// A builder whose array type is only known at runtime.
+        pub enum ArrayBuilderImpl {
+            $( $Abc($AbcArrayBuilder) ),*
+        }
+
+        impl ArrayBuilderImpl {
+            /// The name of the physical type of the arrays built.
+            pub fn type_name(&self) -> &'static str {
+                match self {
+                    $( Self::$Abc(_) => stringify!($AbcArray) ),*
+                }
+            }
+
+            /// Append a value to builder.
+            ///
+            /// Fails without appending anything if the value doesn't match the type of the builder.
+            pub fn push(&mut self, item: Option<ScalarRefImpl<'_>>) -> Result<()> {
+                match (self, item) {
+                    $(
+                        (Self::$Abc(builder), Some(ScalarRefImpl::$Abc(item))) => builder.try_push(Some(item)),
+                        (Self::$Abc(builder), None) => builder.try_push(None),
+                    )*
+                    (builder, Some(item)) => Err(ArrayError::TypeMismatch {
+                        expected: builder.type_name(),
+                        actual: item.type_name(),
+                    }),
+                }
+            }
+
+            /// Check that the values of `array` can be appended to builder.
+            pub(crate) fn check(&self, array: &ArrayImpl) -> Result<()> {
+                match (self, array) {
+                    $( (Self::$Abc(builder), ArrayImpl::$Abc(array)) => builder.check(array), )*
+                    (builder, array) => Err(ArrayError::TypeMismatch {
+                        expected: builder.type_name(),
+                        actual: array.type_name(),
+                    }),
+                }
+            }
+
+            /// Number of values appended so far.
+            pub fn len(&self) -> usize {
+                match self {
+                    $( Self::$Abc(builder) => builder.len() ),*
+                }
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Reserve capacity for at least `additional` more values.
+            pub fn reserve(&mut self, additional: usize) {
+                match self {
+                    $( Self::$Abc(builder) => builder.reserve(additional) ),*
+                }
+            }
+
+            /// Append `n` nulls to builder.
+            pub fn push_n_nulls(&mut self, n: usize) {
+                match self {
+                    $( Self::$Abc(builder) => builder.push_n_nulls(n) ),*
+                }
+            }
+
+            /// Append all values of `array` to builder.
+            ///
+            /// Fails without appending anything if the array doesn't match the type of the builder.
+            pub fn append_array(&mut self, array: &ArrayImpl) -> Result<()> {
+                match (self, array) {
+                    $(
+                        (Self::$Abc(builder), ArrayImpl::$Abc(array)) => {
+                            builder.check(array)?;
+                            builder.append_array(array);
+                            Ok(())
+                        }
+                    )*
+                    (builder, array) => Err(ArrayError::TypeMismatch {
+                        expected: builder.type_name(),
+                        actual: array.type_name(),
+                    }),
+                }
+            }
+
+            pub fn finish(self) -> ArrayImpl {
+                match self {
+                    $( Self::$Abc(builder) => builder.finish().into() ),*
+                }
+            }
+        }
+    };
+}
+
+/// Define `for_each_array!`, which evaluates `$body` with `$array` bound to the concrete array
+/// inside `$input`, and wraps the resulting array of the same type back into an [`ArrayImpl`].
+///
+/// `$d` is a `$` token, for the metavariables of the macro being defined.
+macro_rules! impl_for_each_array {
+    ($d:tt $( { $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty } ),*) => {
+        macro_rules! for_each_array {
+            ($d input:expr, $d array:ident => $d body:expr) => {
+                match $d input {
+                    $( $crate::ArrayImpl::$Abc($d array) => $crate::ArrayImpl::$Abc($d body) ),*
+                }
+            };
+        }
+    };
+}
+
+/// Invoke `$macro` with an entry per variant of [`ArrayImpl`], after the optional `$prefix`.
 macro_rules! impl_for_all {
-    ($macro:tt) => {
+    ($macro:tt $(, $($prefix:tt)*)?) => {
         $macro! {
+            $($($prefix)*)?
             { Int16, int16, Int16Array, Int16ArrayBuilder, i16, i16 },
             { Int32, int32, Int32Array, Int32ArrayBuilder, i32, i32 },
             { Int64, int64, Int64Array, Int64ArrayBuilder, i64, i64 },
             { Float32, float32, Float32Array, Float32ArrayBuilder, f32, f32 },
             { Float64, float64, Float64Array, Float64ArrayBuilder, f64, f64 },
             { Boolean, boolean, BooleanArray, BooleanArrayBuilder, bool, bool },
-            { String, string, StringArray, StringArrayBuilder, String, &'a str }
+            { Decimal, decimal, DecimalArray, DecimalArrayBuilder, Decimal, Decimal },
+            { Date, date, DateArray, DateArrayBuilder, Date, Date },
+            { Time, time, TimeArray, TimeArrayBuilder, Time, Time },
+            { Timestamp, timestamp, TimestampArray, TimestampArrayBuilder, Timestamp, Timestamp },
+            { Interval, interval, IntervalArray, IntervalArrayBuilder, Interval, Interval },
+            { String, string, StringArray, StringArrayBuilder, String, &'a str },
+            { Bytes, bytes, BytesArray, BytesArrayBuilder, Vec<u8>, &'a [u8] },
+            { List, list, ListArray, ListArrayBuilder, ListValue, ListRef<'a> },
+            { Struct, r#struct, StructArray, StructArrayBuilder, StructValue, StructRef<'a> }
         }
     };
 }
@@ -144,6 +360,61 @@ macro_rules! impl_for_all {
 impl_for_all! {impl_scalar_dispatch}
 impl_for_all! {impl_scalar_ref_dispatch}
 impl_for_all! {impl_array_dispatch}
+impl_for_all! {impl_array_builder_dispatch}
+impl_for_all! {impl_for_each_array, $}
+
+impl ArrayBuilderImpl {
+    /// Create a builder for arrays of `data_type` with `capacity`. A builder of decimals only
+    /// accepts values that fit the precision and scale of the type.
+    ///
+    /// Fails if the precision or scale of a decimal type is out of range.
+    pub fn with_capacity(data_type: &DataType, capacity: usize) -> Result<Self> {
+        Ok(match data_type {
+            DataType::SmallInt => Self::Int16(Int16ArrayBuilder::with_capacity(capacity)),
+            DataType::Integer => Self::Int32(Int32ArrayBuilder::with_capacity(capacity)),
+            DataType::BigInt => Self::Int64(Int64ArrayBuilder::with_capacity(capacity)),
+            DataType::Varchar | DataType::Char { .. } => {
+                Self::String(StringArrayBuilder::with_capacity(capacity))
+            }
+            DataType::Bytea => Self::Bytes(BytesArrayBuilder::with_capacity(capacity)),
+            DataType::Boolean => Self::Boolean(BooleanArrayBuilder::with_capacity(capacity)),
+            DataType::Real => Self::Float32(Float32ArrayBuilder::with_capacity(capacity)),
+            DataType::Double => Self::Float64(Float64ArrayBuilder::with_capacity(capacity)),
+            DataType::Decimal { scale, precision } => {
+                let scale = u8::try_from(*scale)
+                    .ok()
+                    .filter(|&scale| scale <= MAX_SCALE)
+                    .ok_or_else(|| {
+                        ArrayError::InvalidArgument(format!("decimal scale {} out of range", scale))
+                    })?;
+                let precision = u8::try_from(*precision)
+                    .ok()
+                    .filter(|precision| (1..=MAX_PRECISION).contains(precision))
+                    .ok_or_else(|| {
+                        ArrayError::InvalidArgument(format!(
+                            "decimal precision {} out of range",
+                            precision
+                        ))
+                    })?;
+                Self::Decimal(DecimalArrayBuilder::with_precision(
+                    capacity, precision, scale,
+                ))
+            }
+            DataType::Date => Self::Date(DateArrayBuilder::with_capacity(capacity)),
+            DataType::Time => Self::Time(TimeArrayBuilder::with_capacity(capacity)),
+            DataType::Timestamp { .. } => {
+                Self::Timestamp(TimestampArrayBuilder::with_capacity(capacity))
+            }
+            DataType::Interval => Self::Interval(IntervalArrayBuilder::with_capacity(capacity)),
+            DataType::List { element } => {
+                Self::List(ListArrayBuilder::with_element(capacity, element)?)
+            }
+            DataType::Struct { fields } => {
+                Self::Struct(StructArrayBuilder::with_fields(capacity, fields)?)
+            }
+        })
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -151,6 +422,19 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_type_mismatch() {
+        let array: ArrayImpl = Int32ArrayBuilder::with_capacity(0).finish().into();
+        let err = <&StringArray>::try_from(&array).unwrap_err();
+        assert_eq!(
+            err,
+            ArrayError::TypeMismatch {
+                expected: "StringArray",
+                actual: "Int32Array",
+            }
+        );
+    }
+
     #[test]
     fn test() {
         let mut array_builder = Int32ArrayBuilder::with_capacity(10);
@@ -164,4 +448,77 @@ mod test {
             _ => panic!(""),
         }
     }
+
+    #[test]
+    fn test_builder_from_data_type() {
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("a"), None]).into();
+        let mut builder = ArrayBuilderImpl::with_capacity(&DataType::Char { width: 1 }, 3).unwrap();
+        builder.push(Some(ScalarRefImpl::String("b"))).unwrap();
+        builder.append_array(&strings).unwrap();
+        let array = builder.finish();
+        assert_eq!(array.type_name(), "StringArray");
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.get(0), Some(ScalarRefImpl::String("b")));
+        assert_eq!(array.get(1), Some(ScalarRefImpl::String("a")));
+        assert_eq!(array.get(2), None);
+
+        let data_type = DataType::List {
+            element: Box::new(DataType::Integer),
+        };
+        let array = ArrayBuilderImpl::with_capacity(&data_type, 0)
+            .unwrap()
+            .finish();
+        assert_eq!(array.type_name(), "ListArray");
+        assert!(array.is_empty());
+        let array: ListArray = array.try_into().unwrap();
+        assert_eq!(array.child().unwrap().type_name(), "Int32Array");
+    }
+
+    #[test]
+    fn test_append_mismatch() {
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("a")]).into();
+        let mut builder = ArrayBuilderImpl::with_capacity(&DataType::Integer, 1).unwrap();
+        assert_eq!(
+            builder.append_array(&strings),
+            Err(ArrayError::TypeMismatch {
+                expected: "Int32Array",
+                actual: "StringArray",
+            })
+        );
+        assert!(builder.push(Some(ScalarRefImpl::Int16(1))).is_err());
+        assert!(builder.is_empty());
+    }
+
+    #[test]
+    fn test_decimal_builder_scale() {
+        let data_type = DataType::Decimal {
+            scale: 2,
+            precision: 10,
+        };
+        let mut builder = ArrayBuilderImpl::with_capacity(&data_type, 1).unwrap();
+        builder
+            .push(Some(ScalarRefImpl::Decimal(Decimal::from(7i32))))
+            .unwrap();
+        let array: DecimalArray = builder.finish().try_into().unwrap();
+        assert_eq!(array.scale(), 2);
+        assert_eq!(array.new_builder(0).finish().scale(), 2);
+
+        // Values that don't fit are rejected without appending anything.
+        let mut builder = ArrayBuilderImpl::with_capacity(&data_type, 1).unwrap();
+        let huge = Decimal::new(i128::MAX, 0).unwrap();
+        assert!(matches!(
+            builder.push(Some(ScalarRefImpl::Decimal(huge))),
+            Err(ArrayError::Overflow(_))
+        ));
+        let digits = Decimal::from(123_456_789i32);
+        assert!(builder.push(Some(ScalarRefImpl::Decimal(digits))).is_err());
+        let wide: ArrayImpl = DecimalArray::from_slice(&[Some(huge)]).into();
+        assert!(builder.append_array(&wide).is_err());
+        assert!(builder.is_empty());
+
+        for (scale, precision) in [(300, 10), (2, 0), (2, 39)] {
+            let data_type = DataType::Decimal { scale, precision };
+            assert!(ArrayBuilderImpl::with_capacity(&data_type, 1).is_err());
+        }
+    }
 }
@@ -0,0 +1,70 @@
+use thiserror::Error;
+
+/// The error type of all fallible operations in this crate.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ArrayError {
+    /// A value or array is not of the type an operation expects.
+    #[error("type mismatch: expected {expected}, found {actual}")]
+    TypeMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// A function got the wrong number of arguments.
+    #[error("expected {expected} arguments, found {actual}")]
+    ArityMismatch { expected: usize, actual: usize },
+    /// Arrays that are processed row by row have different lengths.
+    #[error("length mismatch: {left} rows vs {right} rows")]
+    LengthMismatch { left: usize, right: usize },
+    /// A result doesn't fit in its type.
+    #[error("{0} out of range")]
+    Overflow(String),
+    #[error("division by zero")]
+    DivisionByZero,
+    /// An argument has a value the function can't accept.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    /// A string can't be parsed as a value of the target type.
+    #[error("invalid input syntax for {target}: {input:?}")]
+    Parse { target: &'static str, input: String },
+    #[error("invalid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+    /// No implementation exists for the requested operation or types.
+    #[error("unsupported: {0}")]
+    Unsupported(String),
+    /// Another error, with a message describing what was being done.
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        source: Box<ArrayError>,
+    },
+}
+
+impl ArrayError {
+    /// Wrap this error with a message describing what was being done.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+pub type Result<T, E = ArrayError> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = ArrayError::TypeMismatch {
+            expected: "Int32Array",
+            actual: "StringArray",
+        }
+        .context("evaluating argument 0");
+        assert_eq!(
+            err.to_string(),
+            "evaluating argument 0: type mismatch: expected Int32Array, found StringArray"
+        );
+    }
+}
@@ -1,12 +1,266 @@
-#![allow(unused)]
-
 use crate::{
-    array_impl::StringArray, data_type::DataType, dispatch::ArrayImpl, prelude::BooleanArray,
-    Array, ArrayBuilder, Scalar, ScalarRef,
+    dispatch::{ArrayImpl, ScalarRefImpl},
+    Array, ArrayBuilder, ArrayError, ArrayIterator, Result, Scalar,
 };
 
 pub trait Expression {
-    fn eval_batch(&self, arrays: &[&ArrayImpl]) -> Result<ArrayImpl, ()>;
+    /// Evaluate over inputs that are either arrays or constants.
+    /// Constants are broadcast to the length of the array inputs.
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl>;
+
+    fn eval_batch(&self, arrays: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        let inputs: Vec<_> = arrays.iter().map(|array| Datum::Array(array)).collect();
+        self.eval_datums(&inputs)
+    }
+}
+
+/// An input of an expression: a column of values, or a constant shared by every row.
+#[derive(Debug, Clone, Copy)]
+pub enum Datum<'a> {
+    Array(&'a ArrayImpl),
+    Scalar(Option<ScalarRefImpl<'a>>),
+}
+
+impl<'a> Datum<'a> {
+    /// The number of rows produced from `inputs`.
+    ///
+    /// All arrays must have the same length. If there are only constants, there's a single row.
+    pub fn batch_len(inputs: &[Datum<'_>]) -> Result<usize> {
+        let mut len = None;
+        for input in inputs {
+            if let Datum::Array(array) = input {
+                match len {
+                    None => len = Some(array.len()),
+                    Some(left) if left != array.len() => {
+                        return Err(ArrayError::LengthMismatch {
+                            left,
+                            right: array.len(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(len.unwrap_or(1))
+    }
+
+    /// Whether some row of this input is null.
+    pub fn has_nulls(&self) -> bool {
+        match self {
+            Datum::Array(array) => array.null_count() > 0,
+            Datum::Scalar(scalar) => scalar.is_none(),
+        }
+    }
+
+    /// Iterate over `len` rows of this input as values of array type `A`.
+    fn iter<A>(self, len: usize) -> Result<DatumIterator<'a, A>>
+    where
+        A: Array,
+        &'a A: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+        A::RefItem<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    {
+        match self {
+            Datum::Array(array) => Ok(DatumIterator::Array(<&A>::try_from(array)?.iter())),
+            Datum::Scalar(scalar) => Ok(DatumIterator::Scalar {
+                value: scalar.map(TryInto::try_into).transpose()?,
+                remaining: len,
+            }),
+        }
+    }
+
+    /// Iterate over `len` rows of this input as values of array type `A`, without checking for
+    /// nulls. The input must not have any.
+    fn values<A>(self, len: usize) -> Result<DatumValues<'a, A>>
+    where
+        A: Array,
+        &'a A: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+        A::RefItem<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    {
+        debug_assert!(!self.has_nulls());
+        match self {
+            Datum::Array(array) => Ok(DatumValues::Array {
+                array: <&A>::try_from(array)?,
+                index: 0,
+            }),
+            Datum::Scalar(scalar) => Ok(DatumValues::Scalar {
+                value: scalar.expect("null constant").try_into()?,
+                remaining: len,
+            }),
+        }
+    }
+}
+
+impl<'a> From<&'a ArrayImpl> for Datum<'a> {
+    fn from(array: &'a ArrayImpl) -> Self {
+        Datum::Array(array)
+    }
+}
+
+enum DatumIterator<'a, A: Array> {
+    Array(ArrayIterator<'a, A>),
+    Scalar {
+        value: Option<A::RefItem<'a>>,
+        remaining: usize,
+    },
+}
+
+impl<'a, A: Array> Iterator for DatumIterator<'a, A> {
+    type Item = Option<A::RefItem<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            DatumIterator::Array(iter) => iter.next(),
+            DatumIterator::Scalar { value, remaining } => {
+                if *remaining == 0 {
+                    None
+                } else {
+                    *remaining -= 1;
+                    Some(*value)
+                }
+            }
+        }
+    }
+}
+
+/// The values of a [`Datum`] without nulls.
+enum DatumValues<'a, A: Array> {
+    Array {
+        array: &'a A,
+        index: usize,
+    },
+    Scalar {
+        value: A::RefItem<'a>,
+        remaining: usize,
+    },
+}
+
+impl<'a, A: Array> Iterator for DatumValues<'a, A> {
+    type Item = A::RefItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            DatumValues::Array { array, index } => {
+                if *index == array.len() {
+                    None
+                } else {
+                    *index += 1;
+                    Some(array.value(*index - 1))
+                }
+            }
+            DatumValues::Scalar { value, remaining } => {
+                if *remaining == 0 {
+                    None
+                } else {
+                    *remaining -= 1;
+                    Some(*value)
+                }
+            }
+        }
+    }
+}
+
+/// The return type of a scalar function: either a value, or a `Result` for fallible functions.
+pub trait FunctionOutput {
+    type Scalar: Scalar;
+
+    /// Convert into the value to append to the output array. `None` stands for null.
+    fn into_result(self) -> Result<Option<Self::Scalar>>;
+}
+
+impl<T: Scalar> FunctionOutput for T {
+    type Scalar = T;
+
+    fn into_result(self) -> Result<Option<T>> {
+        Ok(Some(self))
+    }
+}
+
+impl<T: Scalar> FunctionOutput for Option<T> {
+    type Scalar = T;
+
+    fn into_result(self) -> Result<Option<T>> {
+        Ok(self)
+    }
+}
+
+impl<T: Scalar> FunctionOutput for Result<T> {
+    type Scalar = T;
+
+    fn into_result(self) -> Result<Option<T>> {
+        self.map(Some)
+    }
+}
+
+pub struct UnaryExpression<I, O, F> {
+    f: F,
+    _marker: std::marker::PhantomData<fn(I) -> O>,
+}
+
+impl<I, O, F> UnaryExpression<I, O, F>
+where
+    I: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(I::RefType<'_>) -> O,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn eval(&self, i: &ArrayImpl) -> Result<ArrayImpl> {
+        self.eval_datum(Datum::Array(i))
+    }
+
+    pub fn eval_datum(&self, i: Datum<'_>) -> Result<ArrayImpl> {
+        let len = Datum::batch_len(&[i])?;
+        let mut builder = <<O::Scalar as Scalar>::ArrayTpye as Array>::Builder::with_capacity(len);
+
+        if !i.has_nulls() {
+            for i in i.values::<I::ArrayTpye>(len)? {
+                let result = (self.f)(i).into_result()?;
+                builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+            }
+            return Ok(builder.finish().into());
+        }
+
+        let i = i.iter::<I::ArrayTpye>(len)?;
+        for i in i {
+            match i {
+                Some(i) => {
+                    let result = (self.f)(i).into_result()?;
+                    builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+                }
+                None => builder.push(None),
+            }
+        }
+
+        Ok(builder.finish().into())
+    }
+}
+
+impl<I, O, F> Expression for UnaryExpression<I, O, F>
+where
+    I: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(I::RefType<'_>) -> O,
+{
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.len() != 1 {
+            return Err(ArrayError::ArityMismatch {
+                expected: 1,
+                actual: inputs.len(),
+            });
+        }
+        self.eval_datum(inputs[0])
+    }
 }
 
 pub struct BinaryExpression<I1, I2, O, F> {
@@ -18,10 +272,12 @@ impl<I1, I2, O, F> BinaryExpression<I1, I2, O, F>
 where
     I1: Scalar,
     I2: Scalar,
-    O: Scalar,
-    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ()>,
-    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ()>,
-    O::ArrayTpye: Into<ArrayImpl>,
+    O: FunctionOutput,
+    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I1::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I2::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
     F: Fn(I1::RefType<'_>, I2::RefType<'_>) -> O,
 {
     pub fn new(f: F) -> Self {
@@ -31,18 +287,40 @@ where
         }
     }
 
-    pub fn eval(&self, i1: &ArrayImpl, i2: &ArrayImpl) -> Result<ArrayImpl, ()> {
-        assert!(i1.len() == i2.len(), "size mismatch");
+    pub fn eval(&self, i1: &ArrayImpl, i2: &ArrayImpl) -> Result<ArrayImpl> {
+        self.eval_datum(Datum::Array(i1), Datum::Array(i2))
+    }
+
+    /// Evaluate over two inputs, broadcasting a constant input to the length of the other.
+    pub fn eval_datum(&self, i1: Datum<'_>, i2: Datum<'_>) -> Result<ArrayImpl> {
+        let len = Datum::batch_len(&[i1, i2])?;
+        let mut builder = <<O::Scalar as Scalar>::ArrayTpye as Array>::Builder::with_capacity(len);
 
-        let i1: &I1::ArrayTpye = i1.try_into()?;
-        let i2: &I2::ArrayTpye = i2.try_into()?;
+        if !i1.has_nulls() && !i2.has_nulls() {
+            let i1 = i1
+                .values::<I1::ArrayTpye>(len)
+                .map_err(|err| err.context("first argument"))?;
+            let i2 = i2
+                .values::<I2::ArrayTpye>(len)
+                .map_err(|err| err.context("second argument"))?;
+            for (i1, i2) in i1.zip(i2) {
+                let result = (self.f)(i1, i2).into_result()?;
+                builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+            }
+            return Ok(builder.finish().into());
+        }
 
-        let mut builder = <O::ArrayTpye as Array>::Builder::with_capacity(i1.len());
-        for (i1, i2) in i1.iter().zip(i2.iter()) {
+        let i1 = i1
+            .iter::<I1::ArrayTpye>(len)
+            .map_err(|err| err.context("first argument"))?;
+        let i2 = i2
+            .iter::<I2::ArrayTpye>(len)
+            .map_err(|err| err.context("second argument"))?;
+        for (i1, i2) in i1.zip(i2) {
             match (i1, i2) {
                 (Some(i1), Some(i2)) => {
-                    let result = (self.f)(i1, i2);
-                    builder.push(Some(result.as_scalar_ref()));
+                    let result = (self.f)(i1, i2).into_result()?;
+                    builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
                 }
                 _ => builder.push(None),
             }
@@ -56,16 +334,364 @@ impl<I1, I2, O, F> Expression for BinaryExpression<I1, I2, O, F>
 where
     I1: Scalar,
     I2: Scalar,
-    O: Scalar,
-    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ()>,
-    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ()>,
-    O::ArrayTpye: Into<ArrayImpl>,
+    O: FunctionOutput,
+    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I1::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I2::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
     F: Fn(I1::RefType<'_>, I2::RefType<'_>) -> O,
 {
-    fn eval_batch(&self, arrays: &[&ArrayImpl]) -> Result<ArrayImpl, ()> {
-        if arrays.len() != 2 {
-            return Err(());
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.len() != 2 {
+            return Err(ArrayError::ArityMismatch {
+                expected: 2,
+                actual: inputs.len(),
+            });
+        }
+        self.eval_datum(inputs[0], inputs[1])
+    }
+}
+
+/// A binary expression whose function also sees null inputs, instead of producing null for them.
+pub struct NullableBinaryExpression<I1, I2, O, F> {
+    f: F,
+    _marker: std::marker::PhantomData<fn(I1, I2) -> O>,
+}
+
+impl<I1, I2, O, F> NullableBinaryExpression<I1, I2, O, F>
+where
+    I1: Scalar,
+    I2: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I1::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I2::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(Option<I1::RefType<'_>>, Option<I2::RefType<'_>>) -> O,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn eval(&self, i1: &ArrayImpl, i2: &ArrayImpl) -> Result<ArrayImpl> {
+        self.eval_datum(Datum::Array(i1), Datum::Array(i2))
+    }
+
+    /// Evaluate over two inputs, broadcasting a constant input to the length of the other.
+    pub fn eval_datum(&self, i1: Datum<'_>, i2: Datum<'_>) -> Result<ArrayImpl> {
+        let len = Datum::batch_len(&[i1, i2])?;
+
+        let i1 = i1
+            .iter::<I1::ArrayTpye>(len)
+            .map_err(|err| err.context("first argument"))?;
+        let i2 = i2
+            .iter::<I2::ArrayTpye>(len)
+            .map_err(|err| err.context("second argument"))?;
+
+        let mut builder = <<O::Scalar as Scalar>::ArrayTpye as Array>::Builder::with_capacity(len);
+        for (i1, i2) in i1.zip(i2) {
+            let result = (self.f)(i1, i2).into_result()?;
+            builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+        }
+
+        Ok(builder.finish().into())
+    }
+}
+
+impl<I1, I2, O, F> Expression for NullableBinaryExpression<I1, I2, O, F>
+where
+    I1: Scalar,
+    I2: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I1::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I2::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(Option<I1::RefType<'_>>, Option<I2::RefType<'_>>) -> O,
+{
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.len() != 2 {
+            return Err(ArrayError::ArityMismatch {
+                expected: 2,
+                actual: inputs.len(),
+            });
+        }
+        self.eval_datum(inputs[0], inputs[1])
+    }
+}
+
+pub struct TernaryExpression<I1, I2, I3, O, F> {
+    f: F,
+    _marker: std::marker::PhantomData<fn(I1, I2, I3) -> O>,
+}
+
+impl<I1, I2, I3, O, F> TernaryExpression<I1, I2, I3, O, F>
+where
+    I1: Scalar,
+    I2: Scalar,
+    I3: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I3::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I1::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I2::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I3::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(I1::RefType<'_>, I2::RefType<'_>, I3::RefType<'_>) -> O,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn eval(&self, i1: &ArrayImpl, i2: &ArrayImpl, i3: &ArrayImpl) -> Result<ArrayImpl> {
+        self.eval_datum(Datum::Array(i1), Datum::Array(i2), Datum::Array(i3))
+    }
+
+    /// Evaluate over three inputs, broadcasting constant inputs to the length of the others.
+    pub fn eval_datum(&self, i1: Datum<'_>, i2: Datum<'_>, i3: Datum<'_>) -> Result<ArrayImpl> {
+        let len = Datum::batch_len(&[i1, i2, i3])?;
+        let mut builder = <<O::Scalar as Scalar>::ArrayTpye as Array>::Builder::with_capacity(len);
+
+        if !i1.has_nulls() && !i2.has_nulls() && !i3.has_nulls() {
+            let i1 = i1
+                .values::<I1::ArrayTpye>(len)
+                .map_err(|err| err.context("first argument"))?;
+            let i2 = i2
+                .values::<I2::ArrayTpye>(len)
+                .map_err(|err| err.context("second argument"))?;
+            let i3 = i3
+                .values::<I3::ArrayTpye>(len)
+                .map_err(|err| err.context("third argument"))?;
+            for ((i1, i2), i3) in i1.zip(i2).zip(i3) {
+                let result = (self.f)(i1, i2, i3).into_result()?;
+                builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+            }
+            return Ok(builder.finish().into());
+        }
+
+        let i1 = i1
+            .iter::<I1::ArrayTpye>(len)
+            .map_err(|err| err.context("first argument"))?;
+        let i2 = i2
+            .iter::<I2::ArrayTpye>(len)
+            .map_err(|err| err.context("second argument"))?;
+        let i3 = i3
+            .iter::<I3::ArrayTpye>(len)
+            .map_err(|err| err.context("third argument"))?;
+        for ((i1, i2), i3) in i1.zip(i2).zip(i3) {
+            match (i1, i2, i3) {
+                (Some(i1), Some(i2), Some(i3)) => {
+                    let result = (self.f)(i1, i2, i3).into_result()?;
+                    builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+                }
+                _ => builder.push(None),
+            }
+        }
+
+        Ok(builder.finish().into())
+    }
+}
+
+impl<I1, I2, I3, O, F> Expression for TernaryExpression<I1, I2, I3, O, F>
+where
+    I1: Scalar,
+    I2: Scalar,
+    I3: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I1::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I2::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> &'a I3::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I1::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I2::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    for<'a> I3::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(I1::RefType<'_>, I2::RefType<'_>, I3::RefType<'_>) -> O,
+{
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.len() != 3 {
+            return Err(ArrayError::ArityMismatch {
+                expected: 3,
+                actual: inputs.len(),
+            });
+        }
+        self.eval_datum(inputs[0], inputs[1], inputs[2])
+    }
+}
+
+/// A function over any number of arguments of the same type.
+///
+/// Like the other expressions, a row is null if any of its arguments is null.
+pub struct VariadicExpression<I, O, F> {
+    f: F,
+    _marker: std::marker::PhantomData<fn(I) -> O>,
+}
+
+impl<I, O, F> VariadicExpression<I, O, F>
+where
+    I: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(&[I::RefType<'_>]) -> O,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<I, O, F> Expression for VariadicExpression<I, O, F>
+where
+    I: Scalar,
+    O: FunctionOutput,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    <O::Scalar as Scalar>::ArrayTpye: Into<ArrayImpl>,
+    F: Fn(&[I::RefType<'_>]) -> O,
+{
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.is_empty() {
+            return Err(ArrayError::ArityMismatch {
+                expected: 1,
+                actual: 0,
+            });
+        }
+        let len = Datum::batch_len(inputs)?;
+        let mut builder = <<O::Scalar as Scalar>::ArrayTpye as Array>::Builder::with_capacity(len);
+        let mut row = Vec::with_capacity(inputs.len());
+
+        if inputs.iter().all(|input| !input.has_nulls()) {
+            let mut values = inputs
+                .iter()
+                .enumerate()
+                .map(|(i, input)| {
+                    input
+                        .values::<I::ArrayTpye>(len)
+                        .map_err(|err| err.context(format!("argument {}", i)))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            for _ in 0..len {
+                row.clear();
+                row.extend(values.iter_mut().map(|values| values.next().unwrap()));
+                let result = (self.f)(&row).into_result()?;
+                builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+            }
+            return Ok(builder.finish().into());
         }
-        self.eval(&arrays[0], &arrays[1])
+
+        let mut iters = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                input
+                    .iter::<I::ArrayTpye>(len)
+                    .map_err(|err| err.context(format!("argument {}", i)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for _ in 0..len {
+            row.clear();
+            for iter in &mut iters {
+                if let Some(value) = iter.next().unwrap() {
+                    row.push(value);
+                }
+            }
+            if row.len() == inputs.len() {
+                let result = (self.f)(&row).into_result()?;
+                builder.try_push(result.as_ref().map(Scalar::as_scalar_ref))?;
+            } else {
+                builder.push(None);
+            }
+        }
+
+        Ok(builder.finish().into())
+    }
+}
+
+/// Returns the first non-null argument of each row, or null if all of them are null.
+pub struct CoalesceExpression<I> {
+    _marker: std::marker::PhantomData<fn(I) -> I>,
+}
+
+impl<I> CoalesceExpression<I>
+where
+    I: Scalar,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    I::ArrayTpye: Into<ArrayImpl>,
+{
+    pub fn new() -> Self {
+        Self {
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<I> Default for CoalesceExpression<I>
+where
+    I: Scalar,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    I::ArrayTpye: Into<ArrayImpl>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Expression for CoalesceExpression<I>
+where
+    I: Scalar,
+    for<'a> &'a I::ArrayTpye: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+    for<'a> I::RefType<'a>: TryFrom<ScalarRefImpl<'a>, Error = ArrayError>,
+    I::ArrayTpye: Into<ArrayImpl>,
+{
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.is_empty() {
+            return Err(ArrayError::ArityMismatch {
+                expected: 1,
+                actual: 0,
+            });
+        }
+        let len = Datum::batch_len(inputs)?;
+        let mut iters = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                input
+                    .iter::<I::ArrayTpye>(len)
+                    .map_err(|err| err.context(format!("argument {}", i)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut builder = <I::ArrayTpye as Array>::Builder::with_capacity(len);
+        if !inputs[0].has_nulls() {
+            // Every row takes the value of the first argument.
+            for value in inputs[0].values::<I::ArrayTpye>(len)? {
+                builder.try_push(Some(value))?;
+            }
+            return Ok(builder.finish().into());
+        }
+        for _ in 0..len {
+            // Advance every iterator, and keep the first non-null value.
+            let value = iters
+                .iter_mut()
+                .fold(None, |first, iter| first.or(iter.next().unwrap()));
+            builder.try_push(value)?;
+        }
+
+        Ok(builder.finish().into())
     }
 }
@@ -1,4 +1,10 @@
-use crate::{BinaryExpression, DataType, Expression, Scalar};
+use std::cmp::Ordering;
+
+use crate::{
+    function_name, lookup_function, ArrayError, BinaryExpression, CoalesceExpression, DataType,
+    Date, Decimal, Expression, Interval, NullableBinaryExpression, Result, Scalar,
+    TernaryExpression, Time, Timestamp, UnaryExpression, VariadicExpression,
+};
 
 macro_rules! int16 {
     ($macro: tt) => {
@@ -24,6 +30,48 @@ macro_rules! boolean {
     };
 }
 
+macro_rules! decimal {
+    ($macro: tt) => {
+        $macro! { DataType::Decimal{..}, Decimal, DecimalArray }
+    };
+}
+
+macro_rules! date {
+    ($macro: tt) => {
+        $macro! { DataType::Date, Date, DateArray }
+    };
+}
+
+macro_rules! time {
+    ($macro: tt) => {
+        $macro! { DataType::Time, Time, TimeArray }
+    };
+}
+
+macro_rules! timestamp {
+    ($macro: tt) => {
+        $macro! { DataType::Timestamp{..}, Timestamp, TimestampArray }
+    };
+}
+
+macro_rules! interval {
+    ($macro: tt) => {
+        $macro! { DataType::Interval, Interval, IntervalArray }
+    };
+}
+
+macro_rules! float32 {
+    ($macro: tt) => {
+        $macro! { DataType::Real, f32, Float32Array }
+    };
+}
+
+macro_rules! float64 {
+    ($macro: tt) => {
+        $macro! { DataType::Double, f64, Float64Array }
+    };
+}
+
 macro_rules! varchar {
     ($macro: tt) => {
         $macro! { DataType::Varchar, String, StringArray }
@@ -36,6 +84,12 @@ macro_rules! char {
     };
 }
 
+macro_rules! bytea {
+    ($macro: tt) => {
+        $macro! { DataType::Bytea, Vec<u8>, BytesArray }
+    };
+}
+
 macro_rules! data_type_pattern {
     ($data_type_pattern: pat, $scalar_type: ty, $array_type: ty) => {
         $data_type_pattern
@@ -46,18 +100,13 @@ macro_rules! scalar_type {
         $scalar_type
     };
 }
-macro_rules! array_type {
-    ($data_type_pattern: pat, $scalar_type: ty, $array_type: ty) => {
-        $array_type
-    };
-}
 
 macro_rules! impl_cmp_for {
-    ($cmp_func: ident, $l: ident, $r: ident, $({$ty1: tt, $ty2: tt, $convert_ty: tt}),*) => {
+    ($expr: ident, $cmp_func: ident, $l: ident, $r: ident, $({$ty1: tt, $ty2: tt, $convert_ty: tt}),*) => {
         match ($l, $r) {
             $(
                 ($ty1!{ data_type_pattern }, $ty2!{ data_type_pattern }) => {
-                    Ok(Box::new(BinaryExpression::<
+                    Ok(Box::new($expr::<
                             $ty1!{ scalar_type },
                             $ty2!{ scalar_type },
                             bool,
@@ -71,40 +120,350 @@ macro_rules! impl_cmp_for {
                     )))
                 }
             )*
-            _ => {
-                Err(Unsupported)
+            (l, r) => {
+                Err(ArrayError::Unsupported(format!(
+                    "{} between {:?} and {:?}", stringify!($cmp_func), l, r
+                )))
+            }
+        }
+    };
+}
+
+macro_rules! impl_arith_for {
+    ($func: ident, $l: ident, $r: ident, $({$ty1: tt, $ty2: tt, $convert_ty: tt}),*) => {
+        match ($l, $r) {
+            $(
+                ($ty1!{ data_type_pattern }, $ty2!{ data_type_pattern }) => {
+                    Ok(Box::new(BinaryExpression::<
+                            $ty1!{ scalar_type },
+                            $ty2!{ scalar_type },
+                            Result<$convert_ty!{ scalar_type }>,
+                            _
+                        >::new(
+                        $func::<
+                            $ty1!{ scalar_type },
+                            $ty2!{ scalar_type },
+                            $convert_ty!{ scalar_type }
+                        >
+                    )))
+                }
+            )*
+            (l, r) => {
+                Err(ArrayError::Unsupported(format!(
+                    "{} between {:?} and {:?}", stringify!($func), l, r
+                )))
+            }
+        }
+    };
+}
+
+macro_rules! impl_unary_for {
+    ($func: ident, $i: ident, $({$ty: tt}),*) => {
+        match $i {
+            $(
+                $ty!{ data_type_pattern } => {
+                    Ok(Box::new(UnaryExpression::<$ty!{ scalar_type }, _, _>::new(
+                        $func::<$ty!{ scalar_type }>
+                    )))
+                }
+            )*
+            i => {
+                Err(ArrayError::Unsupported(format!(
+                    "{} on {:?}", stringify!($func), i
+                )))
             }
         }
     };
 }
 
+macro_rules! for_all_signed {
+    ($macro: tt, $($param:ident),*) => {
+        $macro! {
+            $($param),*,
+            {int16},
+            {int32},
+            {int64},
+            {float32},
+            {float64},
+            {decimal}
+        }
+    };
+}
+
+macro_rules! impl_coalesce_for {
+    ($args: ident, $({$ty: tt}),*) => {
+        match $args.first() {
+            $(
+                Some($ty!{ data_type_pattern })
+                    if $args.iter().all(|arg| matches!(arg, $ty!{ data_type_pattern })) =>
+                {
+                    Ok(Box::new(CoalesceExpression::<$ty!{ scalar_type }>::new()))
+                }
+            )*
+            Some(_) => Err(ArrayError::Unsupported(format!("coalesce on {:?}", $args))),
+            None => Err(ArrayError::ArityMismatch {
+                expected: 1,
+                actual: 0,
+            }),
+        }
+    };
+}
+
+macro_rules! for_all_primitive {
+    ($macro: tt, $($param:ident),*) => {
+        $macro! {
+            $($param),*,
+            {boolean},
+            {int16},
+            {int32},
+            {int64},
+            {float32},
+            {float64},
+            {decimal},
+            {date},
+            {time},
+            {timestamp},
+            {interval},
+            {varchar},
+            {char},
+            {bytea}
+        }
+    };
+}
+
+/// Operand pairs of arithmetic operators, and the type both sides are promoted to.
+macro_rules! for_all_arith {
+    ($macro: tt, $($param:ident),*) => {
+        $macro! {
+            $($param),*,
+            {int16, int16, int16},
+            {int16, int32, int32},
+            {int16, int64, int64},
+            {int32, int16, int32},
+            {int32, int32, int32},
+            {int32, int64, int64},
+            {int64, int16, int64},
+            {int64, int32, int64},
+            {int64, int64, int64},
+            {float32, float32, float32},
+            {float32, float64, float64},
+            {float64, float32, float64},
+            {float64, float64, float64},
+            {int16, float32, float32},
+            {float32, int16, float32},
+            {int16, float64, float64},
+            {float64, int16, float64},
+            // `f32` doesn't hold every `i32`, and `f64` doesn't hold every `i64`: integers
+            // beyond 2^53 are rounded to the nearest `f64`.
+            {int32, float32, float64},
+            {float32, int32, float64},
+            {int32, float64, float64},
+            {float64, int32, float64},
+            {int64, float32, float64},
+            {float32, int64, float64},
+            {int64, float64, float64},
+            {float64, int64, float64},
+            {decimal, decimal, decimal}
+        }
+    };
+}
+
 macro_rules! for_all_cmp {
     ($macro: tt, $($param:ident),*) => {
         $macro! {
             $($param),*,
             {boolean, boolean, boolean},
+            {int16, int16, int16},
             {int16, int32, int32},
+            {int16, int64, int64},
+            {int32, int16, int32},
             {int32, int32, int32},
+            {int32, int64, int64},
+            {int64, int16, int64},
+            {int64, int32, int64},
+            {int64, int64, int64},
+            {float32, float32, float32},
+            {float32, float64, float64},
+            {float64, float32, float64},
+            {float64, float64, float64},
+            // Integers are compared with floats as `f64`, which holds every `i16` and `i32` exactly.
+            // An `i64` beyond 2^53 is rounded to the nearest `f64`, so it may compare equal to a
+            // float that differs from it by less than the rounding.
+            {int16, float32, float64},
+            {int16, float64, float64},
+            {int32, float32, float64},
+            {int32, float64, float64},
+            {int64, float32, float64},
+            {int64, float64, float64},
+            {float32, int16, float64},
+            {float32, int32, float64},
+            {float32, int64, float64},
+            {float64, int16, float64},
+            {float64, int32, float64},
+            {float64, int64, float64},
+            {decimal, decimal, decimal},
+            {date, date, date},
+            {date, timestamp, timestamp},
+            {timestamp, date, timestamp},
+            {timestamp, timestamp, timestamp},
+            {time, time, time},
+            {interval, interval, interval},
             {varchar, varchar, varchar},
-            {char, char, char}
+            {varchar, char, varchar},
+            {char, varchar, varchar},
+            {char, char, char},
+            {bytea, bytea, bytea}
         }
     };
 }
 
-#[derive(Debug)]
-pub struct Unsupported;
+fn unary_arg(args: &[DataType]) -> Result<&DataType> {
+    match args {
+        [i] => Ok(i),
+        _ => Err(ArrayError::ArityMismatch {
+            expected: 1,
+            actual: args.len(),
+        }),
+    }
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::Varchar | DataType::Char { .. })
+}
 
+fn binary_args(args: &[DataType]) -> Result<(&DataType, &DataType)> {
+    match args {
+        [i1, i2] => Ok((i1, i2)),
+        _ => Err(ArrayError::ArityMismatch {
+            expected: 2,
+            actual: args.len(),
+        }),
+    }
+}
+
+/// Build an expression of `expr_type` over arguments of type `args`.
 pub fn build_expression(
     expr_type: ExpressionType,
-    i1: DataType,
-    i2: DataType,
-) -> Result<Box<dyn Expression>, Unsupported> {
+    args: &[DataType],
+) -> Result<Box<dyn Expression>> {
     match expr_type {
-        ExpressionType::CmpGe => for_all_cmp! { impl_cmp_for, cmp_ge, i1, i2 },
-        ExpressionType::CmpLe => for_all_cmp! { impl_cmp_for, cmp_le, i1, i2 },
-        ExpressionType::ConstainsStr => Ok(Box::new(
-            BinaryExpression::<String, String, bool, _>::new(str_contains),
-        )),
+        ExpressionType::CmpEq => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, BinaryExpression, cmp_eq, i1, i2 }
+        }
+        ExpressionType::CmpNe => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, BinaryExpression, cmp_ne, i1, i2 }
+        }
+        ExpressionType::CmpLt => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, BinaryExpression, cmp_lt, i1, i2 }
+        }
+        ExpressionType::CmpLe => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, BinaryExpression, cmp_le, i1, i2 }
+        }
+        ExpressionType::CmpGt => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, BinaryExpression, cmp_gt, i1, i2 }
+        }
+        ExpressionType::CmpGe => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, BinaryExpression, cmp_ge, i1, i2 }
+        }
+        ExpressionType::IsDistinctFrom => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, NullableBinaryExpression, is_distinct_from, i1, i2 }
+        }
+        ExpressionType::IsNotDistinctFrom => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_cmp! { impl_cmp_for, NullableBinaryExpression, is_not_distinct_from, i1, i2 }
+        }
+        ExpressionType::Add => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_arith! { impl_arith_for, add, i1, i2 }
+        }
+        ExpressionType::Sub => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_arith! { impl_arith_for, sub, i1, i2 }
+        }
+        ExpressionType::Mul => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_arith! { impl_arith_for, mul, i1, i2 }
+        }
+        ExpressionType::Div => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_arith! { impl_arith_for, div, i1, i2 }
+        }
+        ExpressionType::Mod => {
+            let (i1, i2) = binary_args(args)?;
+            for_all_arith! { impl_arith_for, modulo, i1, i2 }
+        }
+        ExpressionType::ConstainsStr => match binary_args(args)? {
+            (s1, s2) if is_string(s1) && is_string(s2) => {
+                Ok(Box::new(BinaryExpression::<String, String, bool, _>::new(
+                    str_contains,
+                )))
+            }
+            _ => Err(ArrayError::Unsupported(format!("contains on {:?}", args))),
+        },
+        ExpressionType::Neg => {
+            let i = unary_arg(args)?;
+            for_all_signed! { impl_unary_for, neg, i }
+        }
+        ExpressionType::Abs => {
+            let i = unary_arg(args)?;
+            for_all_signed! { impl_unary_for, abs, i }
+        }
+        ExpressionType::Not => match unary_arg(args)? {
+            DataType::Boolean => Ok(Box::new(UnaryExpression::<bool, _, _>::new(not))),
+            i => Err(ArrayError::Unsupported(format!("not on {:?}", i))),
+        },
+        ExpressionType::Length | ExpressionType::Upper | ExpressionType::Lower => {
+            match unary_arg(args)? {
+                DataType::Varchar | DataType::Char { .. } => {}
+                i => {
+                    return Err(ArrayError::Unsupported(format!(
+                        "string function on {:?}",
+                        i
+                    )))
+                }
+            }
+            Ok(match expr_type {
+                ExpressionType::Length => Box::new(UnaryExpression::<String, _, _>::new(length)),
+                ExpressionType::Upper => Box::new(UnaryExpression::<String, _, _>::new(upper)),
+                _ => Box::new(UnaryExpression::<String, _, _>::new(lower)),
+            })
+        }
+        ExpressionType::Substr => match args {
+            [s, DataType::Integer, DataType::Integer] if is_string(s) => Ok(Box::new(
+                TernaryExpression::<String, i32, i32, _, _>::new(substr),
+            )),
+            _ => Err(ArrayError::Unsupported(format!("substr on {:?}", args))),
+        },
+        ExpressionType::Replace => match args {
+            [s, from, to] if is_string(s) && is_string(from) && is_string(to) => Ok(Box::new(
+                TernaryExpression::<String, String, String, _, _>::new(replace),
+            )),
+            _ => Err(ArrayError::Unsupported(format!("replace on {:?}", args))),
+        },
+        ExpressionType::Concat => {
+            if args.is_empty() {
+                return Err(ArrayError::ArityMismatch {
+                    expected: 1,
+                    actual: 0,
+                });
+            }
+            if !args.iter().all(is_string) {
+                return Err(ArrayError::Unsupported(format!("concat on {:?}", args)));
+            }
+            Ok(Box::new(VariadicExpression::<String, _, _>::new(concat)))
+        }
+        ExpressionType::Coalesce => for_all_primitive! { impl_coalesce_for, args },
+        ExpressionType::Function(name) => match lookup_function(name, args) {
+            Some(function) => Ok((function.build)()),
+            None => Err(ArrayError::Unsupported(format!("{}({:?})", name, args))),
+        },
     }
 }
 
@@ -112,133 +471,772 @@ pub fn str_contains(s1: &str, s2: &str) -> bool {
     s1.contains(s2)
 }
 
-pub fn cmp_ge<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
-    i1: I1::RefType<'_>,
-    i2: I2::RefType<'_>,
-) -> bool
-where
-    for<'a> I1::RefType<'a>: Into<C::RefType<'a>>,
-    for<'a> I2::RefType<'a>: Into<C::RefType<'a>>,
-    for<'a> C::RefType<'a>: Ord,
-{
-    let i1: C::RefType<'_> = I1::upcast_to(i1).into();
-    let i2: C::RefType<'_> = I2::upcast_to(i2).into();
+/// Number of characters in a string.
+pub fn length(s: &str) -> i32 {
+    s.chars().count() as i32
+}
 
-    i1.cmp(&i2) == std::cmp::Ordering::Greater
+pub fn upper(s: &str) -> String {
+    s.to_uppercase()
 }
 
-pub fn cmp_le<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
-    i1: I1::RefType<'_>,
-    i2: I2::RefType<'_>,
-) -> bool
-where
-    for<'a> I1::RefType<'a>: Into<C::RefType<'a>>,
-    for<'a> I2::RefType<'a>: Into<C::RefType<'a>>,
-    for<'a> C::RefType<'a>: Ord,
-{
-    let i1: C::RefType<'_> = I1::upcast_to(i1).into();
-    let i2: C::RefType<'_> = I2::upcast_to(i2).into();
+pub fn lower(s: &str) -> String {
+    s.to_lowercase()
+}
 
-    i1.cmp(&i2) == std::cmp::Ordering::Less
+/// The substring of `len` characters starting at the 1-based position `start`.
+///
+/// As in SQL, positions before the start of the string count towards `len`.
+pub fn substr(s: &str, start: i32, len: i32) -> Result<String> {
+    if len < 0 {
+        return Err(ArrayError::InvalidArgument(format!(
+            "negative substring length {}",
+            len
+        )));
+    }
+    let end = start as i64 + len as i64;
+    let start = start.max(1) as i64;
+    if end <= start {
+        return Ok(String::new());
+    }
+    Ok(s.chars()
+        .skip(start as usize - 1)
+        .take((end - start) as usize)
+        .collect())
 }
 
-pub enum ExpressionType {
-    CmpGe,
-    CmpLe,
-    ConstainsStr,
+/// Replace every occurrence of `from` in `s` with `to`.
+pub fn replace(s: &str, from: &str, to: &str) -> String {
+    if from.is_empty() {
+        return s.to_string();
+    }
+    s.replace(from, to)
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        array_impl::{StringArray, StringArrayBuilder},
-        dispatch::ScalarRefImpl,
-        prelude::BooleanArray,
-        Array, ArrayBuilder,
-    };
+/// Concatenate all arguments. Like the `||` operator, a null argument makes the result null.
+pub fn concat(args: &[&str]) -> String {
+    args.concat()
+}
 
-    use super::*;
+pub fn not(b: bool) -> bool {
+    !b
+}
 
-    #[test]
-    fn test() {
-        let mut array1 = StringArrayBuilder::with_capacity(1);
-        array1.push(Some("hello"));
-        let array1 = array1.finish();
+/// Numeric types whose sign can be changed, with overflow checking.
+pub trait Signed: Scalar + Copy {
+    fn checked_neg(self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+}
 
-        let mut array2 = StringArrayBuilder::with_capacity(1);
-        array2.push(Some("hello"));
-        let array2 = array2.finish();
+macro_rules! impl_signed_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Signed for $ty {
+                fn checked_neg(self) -> Option<Self> {
+                    <$ty>::checked_neg(self)
+                }
+                fn checked_abs(self) -> Option<Self> {
+                    <$ty>::checked_abs(self)
+                }
+            }
+        )*
+    };
+}
 
-        let expression = BinaryExpression::<String, String, bool, _>::new(str_contains);
+macro_rules! impl_signed_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Signed for $ty {
+                fn checked_neg(self) -> Option<Self> {
+                    Some(-self)
+                }
+                fn checked_abs(self) -> Option<Self> {
+                    Some(self.abs())
+                }
+            }
+        )*
+    };
+}
 
-        let result: BooleanArray = expression
-            .eval(&array1.into(), &array2.into())
-            .unwrap()
-            .try_into()
-            .unwrap();
-        assert_eq!(result.len(), 1);
-        assert_eq!(result.get(0), Some(true));
+impl_signed_for_int!(i16, i32, i64);
+impl_signed_for_float!(f32, f64);
+
+impl Signed for Decimal {
+    fn checked_neg(self) -> Option<Self> {
+        Decimal::checked_neg(self)
+    }
+    fn checked_abs(self) -> Option<Self> {
+        Decimal::checked_abs(self)
     }
+}
 
-    #[test]
-    fn test_build_str_contains() {
-        let expr = build_expression(
-            ExpressionType::ConstainsStr,
-            DataType::Varchar,
-            DataType::Varchar,
-        )
-        .unwrap();
+pub fn neg<T: Signed>(v: T) -> Result<T> {
+    v.checked_neg()
+        .ok_or_else(|| ArrayError::Overflow(format!("-({:?})", v)))
+}
 
-        for _ in 0..10 {
-            let result = expr
-                .eval_batch(&[
-                    &StringArray::from_slice(&[Some("000"), Some("111"), None]).into(),
-                    &StringArray::from_slice(&[Some("0"), Some("0"), None]).into(),
-                ])
-                .unwrap();
-            assert_eq!(
-                result.get(0).unwrap(),
-                ScalarRefImpl::Boolean("000".contains("0"))
-            );
-            assert_eq!(
-                result.get(1).unwrap(),
-                ScalarRefImpl::Boolean(str_contains("111", "0"))
-            );
-            assert!(result.get(2).is_none());
-        }
-    }
+pub fn abs<T: Signed>(v: T) -> Result<T> {
+    v.checked_abs()
+        .ok_or_else(|| ArrayError::Overflow(format!("abs({:?})", v)))
+}
 
-    #[test]
-    fn test_build_cmp() {
-        let expr =
-            build_expression(ExpressionType::CmpGe, DataType::Varchar, DataType::Varchar).unwrap();
+/// Numeric types supporting arithmetic with overflow checking.
+///
+/// Float arithmetic follows IEEE 754 and never overflows, but dividing by zero is still an error.
+pub trait Numeric: Scalar + Copy + std::fmt::Debug {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    /// Divide by a non-zero `rhs`.
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    /// Remainder of dividing by a non-zero `rhs`.
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn is_zero(self) -> bool;
+}
 
-        let result = expr
-            .eval_batch(&[
-                &StringArray::from_slice(&[Some("1"), Some("0"), None]).into(),
-                &StringArray::from_slice(&[Some("0"), Some("1"), None]).into(),
-            ])
-            .unwrap();
-        assert_eq!(
-            result.get(0).unwrap(),
-            ScalarRefImpl::Boolean(cmp_ge::<String, String, String>("1", "0"))
-        );
-        assert_eq!(
-            result.get(1).unwrap(),
-            ScalarRefImpl::Boolean(cmp_ge::<String, String, String>("0", "1"))
-        );
-        assert!(result.get(2).is_none());
+macro_rules! impl_numeric_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Numeric for $ty {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_div(self, rhs)
+                }
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    // `MIN % -1` overflows in Rust, but the remainder is well defined.
+                    if rhs == -1 {
+                        Some(0)
+                    } else {
+                        <$ty>::checked_rem(self, rhs)
+                    }
+                }
+                fn is_zero(self) -> bool {
+                    self == 0
+                }
+            }
+        )*
+    };
+}
 
-        let expr =
-            build_expression(ExpressionType::CmpLe, DataType::Varchar, DataType::Varchar).unwrap();
+macro_rules! impl_numeric_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Numeric for $ty {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    Some(self + rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    Some(self - rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    Some(self * rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    Some(self / rhs)
+                }
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    Some(self % rhs)
+                }
+                fn is_zero(self) -> bool {
+                    self == 0.0
+                }
+            }
+        )*
+    };
+}
 
-        let result = expr
-            .eval_batch(&[
-                &StringArray::from_slice(&[Some("1"), Some("0"), None]).into(),
-                &StringArray::from_slice(&[Some("0"), Some("1"), None]).into(),
-            ])
-            .unwrap();
-        assert_eq!(
+impl_numeric_for_int!(i16, i32, i64);
+impl_numeric_for_float!(f32, f64);
+
+impl Numeric for Decimal {
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Decimal::checked_add(self, rhs)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Decimal::checked_sub(self, rhs)
+    }
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Decimal::checked_mul(self, rhs)
+    }
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        Decimal::checked_div(self, rhs)
+    }
+    fn checked_rem(self, rhs: Self) -> Option<Self> {
+        Decimal::checked_rem(self, rhs)
+    }
+    fn is_zero(self) -> bool {
+        self.mantissa() == 0
+    }
+}
+
+/// The conversion of an operand to the type an operator computes in.
+///
+/// It is implemented for lossless conversions, and from `i64` to `f64`, which rounds integers
+/// beyond 2^53 to the nearest float.
+pub trait Promote<T> {
+    fn promote(self) -> T;
+}
+
+impl<T> Promote<T> for T {
+    fn promote(self) -> T {
+        self
+    }
+}
+
+macro_rules! impl_promote_from {
+    ($($from:ty => $($to:ty),*);*) => {
+        $($(
+            impl Promote<$to> for $from {
+                fn promote(self) -> $to {
+                    <$to>::from(self)
+                }
+            }
+        )*)*
+    };
+}
+
+impl_promote_from! {
+    i16 => i32, i64, f32, f64;
+    i32 => i64, f64;
+    f32 => f64;
+    Date => Timestamp
+}
+
+impl Promote<f64> for i64 {
+    fn promote(self) -> f64 {
+        self as f64
+    }
+}
+
+pub fn add<I1: Scalar, I2: Scalar, C: Numeric>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> Result<C>
+where
+    for<'a> I1::RefType<'a>: Promote<C>,
+    for<'a> I2::RefType<'a>: Promote<C>,
+{
+    let (i1, i2): (C, C) = (i1.promote(), i2.promote());
+    i1.checked_add(i2)
+        .ok_or_else(|| ArrayError::Overflow(format!("{:?} + {:?}", i1, i2)))
+}
+
+pub fn sub<I1: Scalar, I2: Scalar, C: Numeric>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> Result<C>
+where
+    for<'a> I1::RefType<'a>: Promote<C>,
+    for<'a> I2::RefType<'a>: Promote<C>,
+{
+    let (i1, i2): (C, C) = (i1.promote(), i2.promote());
+    i1.checked_sub(i2)
+        .ok_or_else(|| ArrayError::Overflow(format!("{:?} - {:?}", i1, i2)))
+}
+
+pub fn mul<I1: Scalar, I2: Scalar, C: Numeric>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> Result<C>
+where
+    for<'a> I1::RefType<'a>: Promote<C>,
+    for<'a> I2::RefType<'a>: Promote<C>,
+{
+    let (i1, i2): (C, C) = (i1.promote(), i2.promote());
+    i1.checked_mul(i2)
+        .ok_or_else(|| ArrayError::Overflow(format!("{:?} * {:?}", i1, i2)))
+}
+
+pub fn div<I1: Scalar, I2: Scalar, C: Numeric>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> Result<C>
+where
+    for<'a> I1::RefType<'a>: Promote<C>,
+    for<'a> I2::RefType<'a>: Promote<C>,
+{
+    let (i1, i2): (C, C) = (i1.promote(), i2.promote());
+    if i2.is_zero() {
+        return Err(ArrayError::DivisionByZero);
+    }
+    // Integer division only overflows for `MIN / -1`.
+    i1.checked_div(i2)
+        .ok_or_else(|| ArrayError::Overflow(format!("{:?} / {:?}", i1, i2)))
+}
+
+/// The remainder of `i1 / i2`, with the sign of `i1`.
+pub fn modulo<I1: Scalar, I2: Scalar, C: Numeric>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> Result<C>
+where
+    for<'a> I1::RefType<'a>: Promote<C>,
+    for<'a> I2::RefType<'a>: Promote<C>,
+{
+    let (i1, i2): (C, C) = (i1.promote(), i2.promote());
+    if i2.is_zero() {
+        return Err(ArrayError::DivisionByZero);
+    }
+    i1.checked_rem(i2)
+        .ok_or_else(|| ArrayError::Overflow(format!("{:?} % {:?}", i1, i2)))
+}
+
+/// A total order over the values of a type, used by comparison operators.
+///
+/// Floats are ordered as in SQL engines: NaN is equal to itself and greater than any other
+/// value, and `-0.0` equals `0.0`.
+pub trait TotalOrd {
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_total_ord_for_ord {
+    ($($ty:ty),*) => {
+        $(
+            impl TotalOrd for $ty {
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_total_ord_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl TotalOrd for $ty {
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    self.partial_cmp(other)
+                        .unwrap_or_else(|| self.is_nan().cmp(&other.is_nan()))
+                }
+            }
+        )*
+    };
+}
+
+impl_total_ord_for_ord!(
+    bool,
+    i16,
+    i32,
+    i64,
+    Decimal,
+    Date,
+    Time,
+    Timestamp,
+    Interval,
+    &str,
+    &[u8]
+);
+impl_total_ord_for_float!(f32, f64);
+
+/// Compare two values after converting both of them to `C`.
+fn cmp<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> Ordering
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    let i1: C::RefType<'_> = I1::upcast_to(i1).promote();
+    let i2: C::RefType<'_> = I2::upcast_to(i2).promote();
+
+    i1.total_cmp(&i2)
+}
+
+pub fn cmp_eq<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    cmp::<I1, I2, C>(i1, i2) == Ordering::Equal
+}
+
+pub fn cmp_ne<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    cmp::<I1, I2, C>(i1, i2) != Ordering::Equal
+}
+
+pub fn cmp_lt<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    cmp::<I1, I2, C>(i1, i2) == Ordering::Less
+}
+
+pub fn cmp_le<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    cmp::<I1, I2, C>(i1, i2) != Ordering::Greater
+}
+
+pub fn cmp_gt<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    cmp::<I1, I2, C>(i1, i2) == Ordering::Greater
+}
+
+pub fn cmp_ge<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: I1::RefType<'_>,
+    i2: I2::RefType<'_>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    cmp::<I1, I2, C>(i1, i2) != Ordering::Less
+}
+
+/// `IS DISTINCT FROM`: like `<>`, but a null equals another null and differs from any value.
+pub fn is_distinct_from<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: Option<I1::RefType<'_>>,
+    i2: Option<I2::RefType<'_>>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    !is_not_distinct_from::<I1, I2, C>(i1, i2)
+}
+
+/// `IS NOT DISTINCT FROM`: like `=`, but a null equals another null and differs from any value.
+pub fn is_not_distinct_from<I1: Scalar, I2: Scalar, C: Scalar + 'static>(
+    i1: Option<I1::RefType<'_>>,
+    i2: Option<I2::RefType<'_>>,
+) -> bool
+where
+    for<'a> I1::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> I2::RefType<'a>: Promote<C::RefType<'a>>,
+    for<'a> C::RefType<'a>: TotalOrd,
+{
+    match (i1, i2) {
+        (Some(i1), Some(i2)) => cmp_eq::<I1, I2, C>(i1, i2),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpressionType {
+    CmpEq,
+    CmpNe,
+    CmpLt,
+    CmpLe,
+    CmpGt,
+    CmpGe,
+    IsDistinctFrom,
+    IsNotDistinctFrom,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    ConstainsStr,
+    Neg,
+    Abs,
+    Not,
+    Length,
+    Upper,
+    Lower,
+    Substr,
+    Replace,
+    Concat,
+    Coalesce,
+    /// A function registered with the [`function`](crate::function) attribute.
+    Function(&'static str),
+}
+
+impl ExpressionType {
+    /// All functions that are not registered with the [`function`](crate::function) attribute.
+    const BUILTIN: [ExpressionType; 24] = [
+        ExpressionType::CmpEq,
+        ExpressionType::CmpNe,
+        ExpressionType::CmpLt,
+        ExpressionType::CmpLe,
+        ExpressionType::CmpGt,
+        ExpressionType::CmpGe,
+        ExpressionType::IsDistinctFrom,
+        ExpressionType::IsNotDistinctFrom,
+        ExpressionType::Add,
+        ExpressionType::Sub,
+        ExpressionType::Mul,
+        ExpressionType::Div,
+        ExpressionType::Mod,
+        ExpressionType::ConstainsStr,
+        ExpressionType::Neg,
+        ExpressionType::Abs,
+        ExpressionType::Not,
+        ExpressionType::Length,
+        ExpressionType::Upper,
+        ExpressionType::Lower,
+        ExpressionType::Substr,
+        ExpressionType::Replace,
+        ExpressionType::Concat,
+        ExpressionType::Coalesce,
+    ];
+
+    /// The name of this function, as used by [`ExpressionType::from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExpressionType::CmpEq => "equal",
+            ExpressionType::CmpNe => "not_equal",
+            ExpressionType::CmpLt => "less_than",
+            ExpressionType::CmpLe => "less_than_or_equal",
+            ExpressionType::CmpGt => "greater_than",
+            ExpressionType::CmpGe => "greater_than_or_equal",
+            ExpressionType::IsDistinctFrom => "is_distinct_from",
+            ExpressionType::IsNotDistinctFrom => "is_not_distinct_from",
+            ExpressionType::Add => "add",
+            ExpressionType::Sub => "subtract",
+            ExpressionType::Mul => "multiply",
+            ExpressionType::Div => "divide",
+            ExpressionType::Mod => "modulus",
+            ExpressionType::ConstainsStr => "contains",
+            ExpressionType::Neg => "neg",
+            ExpressionType::Abs => "abs",
+            ExpressionType::Not => "not",
+            ExpressionType::Length => "length",
+            ExpressionType::Upper => "upper",
+            ExpressionType::Lower => "lower",
+            ExpressionType::Substr => "substr",
+            ExpressionType::Replace => "replace",
+            ExpressionType::Concat => "concat",
+            ExpressionType::Coalesce => "coalesce",
+            ExpressionType::Function(name) => name,
+        }
+    }
+
+    /// Find a builtin or registered function by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::BUILTIN
+            .into_iter()
+            .find(|builtin| builtin.name() == name)
+            .or_else(|| function_name(name).map(ExpressionType::Function))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array_impl::{StringArray, StringArrayBuilder},
+        dispatch::{ArrayImpl, ScalarRefImpl},
+        prelude::{
+            BooleanArray, DateArrayBuilder, Float32ArrayBuilder, Float64ArrayBuilder,
+            Int16ArrayBuilder, Int32Array, Int32ArrayBuilder, TimestampArrayBuilder,
+        },
+        Array, ArrayBuilder, Datum,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut array1 = StringArrayBuilder::with_capacity(1);
+        array1.push(Some("hello"));
+        let array1 = array1.finish();
+
+        let mut array2 = StringArrayBuilder::with_capacity(1);
+        array2.push(Some("hello"));
+        let array2 = array2.finish();
+
+        let expression = BinaryExpression::<String, String, bool, _>::new(str_contains);
+
+        let result: BooleanArray = expression
+            .eval(&array1.into(), &array2.into())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.get(0), Some(true));
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let expression = BinaryExpression::<String, String, bool, _>::new(str_contains);
+        let err = expression
+            .eval(
+                &StringArray::from_slice(&[Some("a"), Some("b")]).into(),
+                &StringArray::from_slice(&[Some("a")]).into(),
+            )
+            .unwrap_err();
+        assert_eq!(err, ArrayError::LengthMismatch { left: 2, right: 1 });
+    }
+
+    #[test]
+    fn test_broadcast_scalar() {
+        let expression = BinaryExpression::<String, String, bool, _>::new(str_contains);
+        let haystack: ArrayImpl = StringArray::from_slice(&[Some("abc"), Some("xyz"), None]).into();
+
+        let result = expression
+            .eval_datum(
+                Datum::Array(&haystack),
+                Datum::Scalar(Some(ScalarRefImpl::String("b"))),
+            )
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(false)));
+        assert_eq!(result.get(2), None);
+
+        let result = expression
+            .eval_datums(&[Datum::Array(&haystack), Datum::Scalar(None)])
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(result.get(0).is_none());
+
+        let err = expression
+            .eval_datum(
+                Datum::Array(&haystack),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(1))),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "second argument: type mismatch: expected String, found Int32"
+        );
+    }
+
+    #[test]
+    fn test_no_nulls() {
+        let expression = BinaryExpression::<String, String, bool, _>::new(str_contains);
+        let haystack = StringArray::from_slice(&[None, Some("abc"), Some("xyz")]);
+        let haystack: ArrayImpl = haystack.slice(1, 2).into();
+        assert_eq!(haystack.null_count(), 0);
+
+        let result = expression
+            .eval_datum(
+                Datum::Array(&haystack),
+                Datum::Scalar(Some(ScalarRefImpl::String("y"))),
+            )
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(false)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.null_count(), 0);
+
+        let result = expression.eval(&haystack, &haystack).unwrap();
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(true)));
+
+        let err = expression
+            .eval_datum(
+                Datum::Array(&haystack),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(1))),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "second argument: type mismatch: expected String, found Int32"
+        );
+    }
+
+    #[test]
+    fn test_build_str_contains() {
+        let expr = build_expression(
+            ExpressionType::ConstainsStr,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+
+        for _ in 0..10 {
+            let result = expr
+                .eval_batch(&[
+                    &StringArray::from_slice(&[Some("000"), Some("111"), None]).into(),
+                    &StringArray::from_slice(&[Some("0"), Some("0"), None]).into(),
+                ])
+                .unwrap();
+            assert_eq!(
+                result.get(0).unwrap(),
+                ScalarRefImpl::Boolean("000".contains("0"))
+            );
+            assert_eq!(
+                result.get(1).unwrap(),
+                ScalarRefImpl::Boolean(str_contains("111", "0"))
+            );
+            assert!(result.get(2).is_none());
+        }
+
+        assert!(build_expression(
+            ExpressionType::ConstainsStr,
+            &[DataType::Integer, DataType::Integer]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_build_cmp() {
+        let expr = build_expression(
+            ExpressionType::CmpGe,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+
+        let result = expr
+            .eval_batch(&[
+                &StringArray::from_slice(&[Some("1"), Some("0"), None]).into(),
+                &StringArray::from_slice(&[Some("0"), Some("1"), None]).into(),
+            ])
+            .unwrap();
+        assert_eq!(
+            result.get(0).unwrap(),
+            ScalarRefImpl::Boolean(cmp_ge::<String, String, String>("1", "0"))
+        );
+        assert_eq!(
+            result.get(1).unwrap(),
+            ScalarRefImpl::Boolean(cmp_ge::<String, String, String>("0", "1"))
+        );
+        assert!(result.get(2).is_none());
+
+        let expr = build_expression(
+            ExpressionType::CmpLe,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+
+        let result = expr
+            .eval_batch(&[
+                &StringArray::from_slice(&[Some("1"), Some("0"), None]).into(),
+                &StringArray::from_slice(&[Some("0"), Some("1"), None]).into(),
+            ])
+            .unwrap();
+        assert_eq!(
             result.get(0).unwrap(),
             ScalarRefImpl::Boolean(cmp_le::<String, String, String>("1", "0"))
         );
@@ -247,5 +1245,491 @@ mod tests {
             ScalarRefImpl::Boolean(cmp_le::<String, String, String>("0", "1"))
         );
         assert!(result.get(2).is_none());
+
+        let expr = build_expression(
+            ExpressionType::CmpEq,
+            &[DataType::Char { width: 1 }, DataType::Varchar],
+        )
+        .unwrap();
+        let result = expr
+            .eval_batch(&[
+                &StringArray::from_slice(&[Some("a"), Some("b")]).into(),
+                &StringArray::from_slice(&[Some("a"), Some("c")]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(false)));
+        assert!(build_expression(
+            ExpressionType::CmpLt,
+            &[DataType::Varchar, DataType::Char { width: 1 }],
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let err = build_expression(
+            ExpressionType::CmpGe,
+            &[DataType::Varchar, DataType::Boolean],
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, ArrayError::Unsupported(_)));
+
+        let expr = build_expression(
+            ExpressionType::CmpGe,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("a")]).into();
+        assert_eq!(
+            expr.eval_batch(&[&strings]).unwrap_err(),
+            ArrayError::ArityMismatch {
+                expected: 2,
+                actual: 1
+            }
+        );
+
+        let mut booleans = <BooleanArray as Array>::Builder::with_capacity(1);
+        booleans.push(Some(true));
+        let err = expr
+            .eval_batch(&[&strings, &booleans.finish().into()])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "second argument: type mismatch: expected StringArray, found BooleanArray"
+        );
+    }
+
+    #[test]
+    fn test_build_cmp_temporal() {
+        let expr = build_expression(
+            ExpressionType::CmpLe,
+            &[DataType::Date, DataType::Timestamp { timezone: None }],
+        )
+        .unwrap();
+
+        let mut dates = DateArrayBuilder::with_capacity(3);
+        dates.push(Some("2022-03-01".parse().unwrap()));
+        dates.push(Some("2022-03-02".parse().unwrap()));
+        dates.push(None);
+
+        let mut timestamps = TimestampArrayBuilder::with_capacity(3);
+        timestamps.push(Some("2022-03-01 00:00:01".parse().unwrap()));
+        timestamps.push(Some("2022-03-01 23:59:59".parse().unwrap()));
+        timestamps.push(Some("2022-03-01".parse().unwrap()));
+
+        let result = expr
+            .eval_batch(&[&dates.finish().into(), &timestamps.finish().into()])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Boolean(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Boolean(false));
+        assert!(result.get(2).is_none());
+    }
+
+    #[test]
+    fn test_build_unary() {
+        let mut ints = <Int32Array as Array>::Builder::with_capacity(3);
+        ints.push(Some(-5));
+        ints.push(None);
+        ints.push(Some(i32::MIN));
+        let ints: ArrayImpl = ints.finish().into();
+
+        let expr = build_expression(ExpressionType::Abs, &[DataType::Integer]).unwrap();
+        let err = expr.eval_batch(&[&ints]).unwrap_err();
+        assert_eq!(err, ArrayError::Overflow("abs(-2147483648)".to_string()));
+
+        let expr = build_expression(ExpressionType::Neg, &[DataType::Integer]).unwrap();
+        let result = expr.eval_datums(&[Datum::Scalar(Some(ScalarRefImpl::Int32(-5)))]);
+        assert_eq!(result.unwrap().get(0), Some(ScalarRefImpl::Int32(5)));
+
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("Straße"), None]).into();
+        let expr = build_expression(ExpressionType::Upper, &[DataType::Varchar]).unwrap();
+        let result = expr.eval_batch(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("STRASSE")));
+        assert_eq!(result.get(1), None);
+
+        let expr = build_expression(ExpressionType::Length, &[DataType::Varchar]).unwrap();
+        let result = expr.eval_batch(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(6)));
+
+        assert!(build_expression(ExpressionType::Not, &[DataType::Integer]).is_err());
+        assert_eq!(
+            build_expression(ExpressionType::Not, &[]).err().unwrap(),
+            ArrayError::ArityMismatch {
+                expected: 1,
+                actual: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_ternary() {
+        let strings: ArrayImpl =
+            StringArray::from_slice(&[Some("hello"), Some("héllo"), None, Some("abc")]).into();
+        let expr = build_expression(
+            ExpressionType::Substr,
+            &[DataType::Varchar, DataType::Integer, DataType::Integer],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&strings),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(0))),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(3))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("he")));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("hé")));
+        assert_eq!(result.get(2), None);
+
+        let err = expr
+            .eval_datums(&[
+                Datum::Array(&strings),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(1))),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(-1))),
+            ])
+            .unwrap_err();
+        assert!(matches!(err, ArrayError::InvalidArgument(_)));
+
+        let expr = build_expression(
+            ExpressionType::Replace,
+            &[DataType::Varchar, DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&strings),
+                Datum::Scalar(Some(ScalarRefImpl::String("l"))),
+                Datum::Scalar(Some(ScalarRefImpl::String("L"))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("heLLo")));
+        assert_eq!(result.get(3), Some(ScalarRefImpl::String("abc")));
+    }
+
+    #[test]
+    fn test_build_variadic() {
+        let a: ArrayImpl = StringArray::from_slice(&[Some("a"), None, Some("c")]).into();
+        let b: ArrayImpl = StringArray::from_slice(&[Some("1"), Some("2"), None]).into();
+
+        let expr = build_expression(
+            ExpressionType::Concat,
+            &[DataType::Varchar, DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&a),
+                Datum::Scalar(Some(ScalarRefImpl::String("-"))),
+                Datum::Array(&b),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("a-1")));
+        assert_eq!(result.get(1), None);
+        assert_eq!(result.get(2), None);
+
+        let expr = build_expression(
+            ExpressionType::Coalesce,
+            &[DataType::Varchar, DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&a),
+                Datum::Array(&b),
+                Datum::Scalar(Some(ScalarRefImpl::String("z"))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("a")));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("2")));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::String("c")));
+
+        // Without nulls, every row is evaluated or taken from the first argument.
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::String("y"))),
+                Datum::Array(&a),
+                Datum::Array(&b),
+            ])
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("y")));
+        let expr = build_expression(
+            ExpressionType::Concat,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let c: ArrayImpl = StringArray::from_slice(&[Some("x"), Some("")]).into();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&c),
+                Datum::Scalar(Some(ScalarRefImpl::String("!"))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("x!")));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("!")));
+
+        assert!(build_expression(
+            ExpressionType::Coalesce,
+            &[DataType::Varchar, DataType::Integer]
+        )
+        .is_err());
+        assert!(build_expression(ExpressionType::Coalesce, &[]).is_err());
+        assert!(build_expression(ExpressionType::Concat, &[]).is_err());
+        let concat = VariadicExpression::<String, _, _>::new(concat);
+        assert_eq!(
+            concat.eval_datums(&[]).unwrap_err(),
+            ArrayError::ArityMismatch {
+                expected: 1,
+                actual: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_arith() {
+        let mut a = Int16ArrayBuilder::with_capacity(3);
+        a.push(Some(7));
+        a.push(None);
+        a.push(Some(i16::MAX));
+        let a: ArrayImpl = a.finish().into();
+
+        let expr = build_expression(
+            ExpressionType::Add,
+            &[DataType::SmallInt, DataType::Integer],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&a),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(1))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(8)));
+        assert_eq!(result.get(1), None);
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Int32(32768)));
+
+        let expr = build_expression(
+            ExpressionType::Mod,
+            &[DataType::SmallInt, DataType::SmallInt],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&a),
+                Datum::Scalar(Some(ScalarRefImpl::Int16(-4))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int16(3)));
+
+        let expr =
+            build_expression(ExpressionType::Div, &[DataType::SmallInt, DataType::Double]).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&a),
+                Datum::Scalar(Some(ScalarRefImpl::Float64(2.0))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Float64(3.5)));
+
+        let expr =
+            build_expression(ExpressionType::Mul, &[DataType::BigInt, DataType::Real]).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int64(3))),
+                Datum::Scalar(Some(ScalarRefImpl::Float32(0.5))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Float64(1.5)));
+        assert!(
+            build_expression(ExpressionType::Sub, &[DataType::Varchar, DataType::Integer]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_arith_errors() {
+        let expr = build_expression(
+            ExpressionType::Add,
+            &[DataType::SmallInt, DataType::SmallInt],
+        )
+        .unwrap();
+        let err = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int16(i16::MAX))),
+                Datum::Scalar(Some(ScalarRefImpl::Int16(1))),
+            ])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "32767 + 1 out of range");
+
+        let expr =
+            build_expression(ExpressionType::Div, &[DataType::Integer, DataType::Integer]).unwrap();
+        let err = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int32(i32::MIN))),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(-1))),
+            ])
+            .unwrap_err();
+        assert!(matches!(err, ArrayError::Overflow(_)));
+
+        let expr =
+            build_expression(ExpressionType::Mod, &[DataType::Integer, DataType::Integer]).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int32(i32::MIN))),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(-1))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(0)));
+
+        for expr_type in [ExpressionType::Div, ExpressionType::Mod] {
+            let expr = build_expression(expr_type, &[DataType::Double, DataType::Integer]).unwrap();
+            let err = expr
+                .eval_datums(&[
+                    Datum::Scalar(Some(ScalarRefImpl::Float64(1.0))),
+                    Datum::Scalar(Some(ScalarRefImpl::Int32(0))),
+                ])
+                .unwrap_err();
+            assert_eq!(err, ArrayError::DivisionByZero);
+        }
+    }
+
+    #[test]
+    fn test_build_cmp_family() {
+        let mut a = Int32ArrayBuilder::with_capacity(4);
+        let mut b = Int16ArrayBuilder::with_capacity(4);
+        for (x, y) in [
+            (Some(1), Some(2)),
+            (Some(2), Some(2)),
+            (Some(3), Some(2)),
+            (None, None),
+        ] {
+            a.push(x);
+            b.push(y);
+        }
+        let a: ArrayImpl = a.finish().into();
+        let b: ArrayImpl = b.finish().into();
+
+        let cases = [
+            (ExpressionType::CmpEq, [false, true, false]),
+            (ExpressionType::CmpNe, [true, false, true]),
+            (ExpressionType::CmpLt, [true, false, false]),
+            (ExpressionType::CmpLe, [true, true, false]),
+            (ExpressionType::CmpGt, [false, false, true]),
+            (ExpressionType::CmpGe, [false, true, true]),
+        ];
+        for (expr_type, expected) in cases {
+            let expr =
+                build_expression(expr_type, &[DataType::Integer, DataType::SmallInt]).unwrap();
+            let result: BooleanArray = expr.eval_batch(&[&a, &b]).unwrap().try_into().unwrap();
+            let expected: Vec<_> = expected.into_iter().map(Some).chain([None]).collect();
+            assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_build_distinct() {
+        let a: ArrayImpl = StringArray::from_slice(&[Some("a"), Some("a"), None, None]).into();
+        let b: ArrayImpl = StringArray::from_slice(&[Some("a"), None, Some("b"), None]).into();
+
+        let expr = build_expression(
+            ExpressionType::IsDistinctFrom,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let result: BooleanArray = expr.eval_batch(&[&a, &b]).unwrap().try_into().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            vec![Some(false), Some(true), Some(true), Some(false)]
+        );
+
+        let expr = build_expression(
+            ExpressionType::IsNotDistinctFrom,
+            &[DataType::Varchar, DataType::Varchar],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[Datum::Array(&a), Datum::Scalar(None)])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(false)));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Boolean(true)));
+    }
+
+    #[test]
+    fn test_build_cmp_float() {
+        let mut a = Float64ArrayBuilder::with_capacity(4);
+        for v in [f64::NAN, f64::NAN, -0.0, f64::INFINITY] {
+            a.push(Some(v));
+        }
+        let mut b = Float32ArrayBuilder::with_capacity(4);
+        for v in [f32::NAN, f32::INFINITY, 0.0, f32::NAN] {
+            b.push(Some(v));
+        }
+        let (a, b): (ArrayImpl, ArrayImpl) = (a.finish().into(), b.finish().into());
+
+        let expr =
+            build_expression(ExpressionType::CmpEq, &[DataType::Double, DataType::Real]).unwrap();
+        let result: BooleanArray = expr.eval_batch(&[&a, &b]).unwrap().try_into().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            vec![Some(true), Some(false), Some(true), Some(false)]
+        );
+
+        let expr =
+            build_expression(ExpressionType::CmpGt, &[DataType::Double, DataType::Real]).unwrap();
+        let result: BooleanArray = expr.eval_batch(&[&a, &b]).unwrap().try_into().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            vec![Some(false), Some(true), Some(false), Some(false)]
+        );
+
+        let expr = build_expression(
+            ExpressionType::CmpLt,
+            &[DataType::Integer, DataType::Double],
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int32(1))),
+                Datum::Array(&a),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Boolean(false)));
+
+        let expr =
+            build_expression(ExpressionType::CmpGe, &[DataType::Real, DataType::BigInt]).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&b),
+                Datum::Scalar(Some(ScalarRefImpl::Int64(0))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Boolean(true)));
+
+        // 2^53 + 1 rounds to 2^53 as a float.
+        let expr =
+            build_expression(ExpressionType::CmpEq, &[DataType::BigInt, DataType::Double]).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int64((1 << 53) + 1))),
+                Datum::Scalar(Some(ScalarRefImpl::Float64((1u64 << 53) as f64))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(true)));
+    }
+
+    #[test]
+    fn test_names() {
+        for expr_type in ExpressionType::BUILTIN {
+            assert_eq!(ExpressionType::from_name(expr_type.name()), Some(expr_type));
+        }
+        assert_eq!(ExpressionType::Sub.name(), "subtract");
+        assert_eq!(
+            ExpressionType::from_name("modulus"),
+            Some(ExpressionType::Mod)
+        );
+        assert_eq!(ExpressionType::from_name("no_such_function"), None);
     }
 }
@@ -0,0 +1,384 @@
+use std::sync::OnceLock;
+
+use crate::{
+    decimal::MAX_PRECISION, kernel, ArrayError, ArrayImpl, DataChunk, DataType, Datum, Expression,
+    ExpressionType, FunctionCatalog, Result, ScalarImpl,
+};
+
+/// A node of an expression tree, before its types are resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprNode {
+    /// The column at this index of the input.
+    InputRef(usize),
+    /// A constant value.
+    Literal(ScalarImpl),
+    /// A function, by the name given by [`ExpressionType::name`], applied to its arguments.
+    FuncCall(String, Vec<ExprNode>),
+}
+
+impl ExprNode {
+    /// Resolve the functions of this tree for input columns of `input_types`.
+    ///
+    /// Functions are looked up in [`FunctionCatalog::builtin`], so arguments are implicitly cast
+    /// as needed.
+    pub fn bind(&self, input_types: &[DataType]) -> Result<BoundExpr> {
+        static CATALOG: OnceLock<FunctionCatalog> = OnceLock::new();
+        let catalog = CATALOG.get_or_init(FunctionCatalog::builtin);
+        self.bind_with(catalog, input_types)
+    }
+
+    /// Resolve the functions of this tree from `catalog`.
+    pub fn bind_with(
+        &self,
+        catalog: &FunctionCatalog,
+        input_types: &[DataType],
+    ) -> Result<BoundExpr> {
+        match self {
+            ExprNode::InputRef(index) => {
+                let data_type = input_types.get(*index).ok_or_else(|| {
+                    ArrayError::InvalidArgument(format!(
+                        "column {} of {} columns",
+                        index,
+                        input_types.len()
+                    ))
+                })?;
+                Ok(BoundExpr {
+                    node: BoundNode::InputRef(*index),
+                    return_type: data_type.clone(),
+                })
+            }
+            ExprNode::Literal(value) => Ok(BoundExpr {
+                node: BoundNode::Literal(value.to_array()),
+                return_type: literal_type(value)?,
+            }),
+            ExprNode::FuncCall(name, children) => {
+                let func = ExpressionType::from_name(name)
+                    .ok_or_else(|| ArrayError::Unsupported(format!("unknown function {}", name)))?;
+                let children = children
+                    .iter()
+                    .map(|child| child.bind_with(catalog, input_types))
+                    .collect::<Result<Vec<_>>>()?;
+                let arg_types: Vec<_> = children
+                    .iter()
+                    .map(|child| child.return_type.clone())
+                    .collect();
+                let (expr, return_type) = catalog.build(func, &arg_types)?;
+                Ok(BoundExpr {
+                    node: BoundNode::FuncCall(expr, children),
+                    return_type,
+                })
+            }
+        }
+    }
+}
+
+/// The type of a literal. Literals of nested types are not supported.
+fn literal_type(value: &ScalarImpl) -> Result<DataType> {
+    Ok(match value {
+        ScalarImpl::Int16(_) => DataType::SmallInt,
+        ScalarImpl::Int32(_) => DataType::Integer,
+        ScalarImpl::Int64(_) => DataType::BigInt,
+        ScalarImpl::Float32(_) => DataType::Real,
+        ScalarImpl::Float64(_) => DataType::Double,
+        ScalarImpl::Boolean(_) => DataType::Boolean,
+        ScalarImpl::Decimal(d) => DataType::Decimal {
+            scale: d.scale() as u16,
+            precision: MAX_PRECISION as u16,
+        },
+        ScalarImpl::Date(_) => DataType::Date,
+        ScalarImpl::Time(_) => DataType::Time,
+        ScalarImpl::Timestamp(_) => DataType::Timestamp { timezone: None },
+        ScalarImpl::Interval(_) => DataType::Interval,
+        ScalarImpl::String(_) => DataType::Varchar,
+        ScalarImpl::Bytes(_) => DataType::Bytea,
+        ScalarImpl::List(_) | ScalarImpl::Struct(_) => {
+            return Err(ArrayError::Unsupported(format!(
+                "literal of type {}",
+                value.type_name()
+            )))
+        }
+    })
+}
+
+/// An expression tree whose functions are resolved, ready to be evaluated.
+pub struct BoundExpr {
+    node: BoundNode,
+    return_type: DataType,
+}
+
+enum BoundNode {
+    InputRef(usize),
+    /// The value, as an array with a single row.
+    Literal(ArrayImpl),
+    FuncCall(Box<dyn Expression>, Vec<BoundExpr>),
+}
+
+/// The result of evaluating a node.
+enum Value<'a> {
+    Column(&'a ArrayImpl),
+    Array(ArrayImpl),
+    /// A value that is the same for every row, as an array with a single row.
+    Constant(&'a ArrayImpl),
+    OwnedConstant(ArrayImpl),
+}
+
+impl Value<'_> {
+    fn datum(&self) -> Datum<'_> {
+        match self {
+            Value::Column(array) => Datum::Array(array),
+            Value::Array(array) => Datum::Array(array),
+            Value::Constant(array) => Datum::Scalar(array.get(0)),
+            Value::OwnedConstant(array) => Datum::Scalar(array.get(0)),
+        }
+    }
+}
+
+impl BoundExpr {
+    pub fn return_type(&self) -> &DataType {
+        &self.return_type
+    }
+
+    /// Evaluate over a batch of `columns`, which must have the same length.
+    ///
+    /// Without any column, a single row is produced.
+    pub fn eval(&self, columns: &[ArrayImpl]) -> Result<ArrayImpl> {
+        let inputs: Vec<_> = columns.iter().map(Datum::Array).collect();
+        let len = Datum::batch_len(&inputs)?;
+        self.eval_with_len(columns, len)
+    }
+
+    /// Evaluate over the visible rows of `chunk`.
+    ///
+    /// The result has a row for every row of the columns of the chunk. Invisible rows are null,
+    /// and are passed to functions as nulls, so they can't raise errors.
+    pub fn eval_chunk(&self, chunk: &DataChunk) -> Result<ArrayImpl> {
+        let Some(visibility) = chunk.visibility() else {
+            return self.eval_with_len(chunk.columns(), chunk.capacity());
+        };
+        // The columns are evaluated in place, with the invisible rows turned into nulls. Only the
+        // validity of the columns referenced by the expression is rebuilt.
+        let mut used = vec![false; chunk.columns().len()];
+        self.collect_input_refs(&mut used);
+        let columns: Vec<_> = chunk
+            .columns()
+            .iter()
+            .zip(used)
+            .map(|(column, used)| match used {
+                true => kernel::mask(column, visibility),
+                false => column.clone(),
+            })
+            .collect();
+        let result = self.eval_with_len(&columns, chunk.capacity())?;
+        Ok(kernel::mask(&result, visibility))
+    }
+
+    /// Mark the columns referenced by this expression.
+    fn collect_input_refs(&self, used: &mut [bool]) {
+        match &self.node {
+            BoundNode::InputRef(index) => {
+                if let Some(used) = used.get_mut(*index) {
+                    *used = true;
+                }
+            }
+            BoundNode::Literal(_) => {}
+            BoundNode::FuncCall(_, children) => {
+                for child in children {
+                    child.collect_input_refs(used);
+                }
+            }
+        }
+    }
+
+    /// Evaluate over `columns`, broadcasting a constant result to `len` rows.
+    fn eval_with_len(&self, columns: &[ArrayImpl], len: usize) -> Result<ArrayImpl> {
+        match self.eval_node(columns)? {
+            Value::Column(array) => Ok(array.clone()),
+            Value::Array(array) => Ok(array),
+            Value::Constant(array) => Ok(broadcast(array, len)),
+            Value::OwnedConstant(array) => Ok(broadcast(&array, len)),
+        }
+    }
+
+    fn eval_node<'a>(&'a self, columns: &'a [ArrayImpl]) -> Result<Value<'a>> {
+        match &self.node {
+            BoundNode::InputRef(index) => {
+                columns
+                    .get(*index)
+                    .map(Value::Column)
+                    .ok_or(ArrayError::ArityMismatch {
+                        expected: *index + 1,
+                        actual: columns.len(),
+                    })
+            }
+            BoundNode::Literal(array) => Ok(Value::Constant(array)),
+            BoundNode::FuncCall(expr, children) => {
+                let values = children
+                    .iter()
+                    .map(|child| child.eval_node(columns))
+                    .collect::<Result<Vec<_>>>()?;
+                let inputs: Vec<_> = values.iter().map(Value::datum).collect();
+                let result = expr.eval_datums(&inputs)?;
+                // A function of constants is evaluated once, and stays a constant.
+                if inputs.iter().all(|input| matches!(input, Datum::Scalar(_))) {
+                    Ok(Value::OwnedConstant(result))
+                } else {
+                    Ok(Value::Array(result))
+                }
+            }
+        }
+    }
+}
+
+/// Repeat the single row of `array` `len` times.
+fn broadcast(array: &ArrayImpl, len: usize) -> ArrayImpl {
+    kernel::gather(array, &vec![0; len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        array_impl::StringArray,
+        prelude::{Int16ArrayBuilder, Int32ArrayBuilder},
+        ArrayBuilder, ScalarRefImpl,
+    };
+
+    fn call(name: &str, children: Vec<ExprNode>) -> ExprNode {
+        ExprNode::FuncCall(name.to_string(), children)
+    }
+
+    #[test]
+    fn test_eval_tree() {
+        // a + 1 >= b * 2
+        let tree = call(
+            "greater_than_or_equal",
+            vec![
+                call(
+                    "add",
+                    vec![
+                        ExprNode::InputRef(0),
+                        ExprNode::Literal(ScalarImpl::Int32(1)),
+                    ],
+                ),
+                call(
+                    "multiply",
+                    vec![
+                        ExprNode::InputRef(1),
+                        ExprNode::Literal(ScalarImpl::Int16(2)),
+                    ],
+                ),
+            ],
+        );
+        let expr = tree.bind(&[DataType::Integer, DataType::SmallInt]).unwrap();
+        assert_eq!(expr.return_type(), &DataType::Boolean);
+
+        let mut a = Int32ArrayBuilder::with_capacity(3);
+        let mut b = Int16ArrayBuilder::with_capacity(3);
+        for (x, y) in [(Some(3), Some(2)), (Some(3), Some(1)), (None, Some(0))] {
+            a.push(x);
+            b.push(y);
+        }
+        let columns = [a.finish().into(), b.finish().into()];
+        let result = expr.eval(&columns).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(2), None);
+
+        let mut b = Int16ArrayBuilder::with_capacity(1);
+        b.push(Some(4));
+        let columns = [columns[0].clone(), b.finish().into()];
+        assert!(matches!(
+            expr.eval(&columns),
+            Err(ArrayError::LengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_eval_constant() {
+        let tree = call(
+            "upper",
+            vec![ExprNode::Literal(ScalarImpl::String("a".to_string()))],
+        );
+        let expr = tree.bind(&[DataType::Varchar]).unwrap();
+        let columns = [StringArray::from_slice(&[Some("x"), None]).into()];
+        let result = expr.eval(&columns).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("A")));
+
+        let result = expr.eval(&[]).unwrap();
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn test_bind_errors() {
+        assert!(ExprNode::InputRef(1).bind(&[DataType::Integer]).is_err());
+        assert!(call("no_such_function", vec![]).bind(&[]).is_err());
+        assert!(call(
+            "add",
+            vec![
+                ExprNode::InputRef(0),
+                ExprNode::Literal(ScalarImpl::Boolean(true))
+            ]
+        )
+        .bind(&[DataType::Integer])
+        .is_err());
+    }
+
+    #[test]
+    fn test_eval_chunk() {
+        use std::sync::Arc;
+
+        use crate::{prelude::BooleanArrayBuilder, Field, Schema};
+
+        // 10 / a, with the row where a = 0 filtered out.
+        let tree = call(
+            "divide",
+            vec![
+                ExprNode::Literal(ScalarImpl::Int32(10)),
+                ExprNode::InputRef(0),
+            ],
+        );
+        let expr = tree.bind(&[DataType::Integer, DataType::Varchar]).unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Integer, true),
+            Field::new("b", DataType::Varchar, true),
+        ]));
+        let mut a = Int32ArrayBuilder::with_capacity(3);
+        let mut mask = BooleanArrayBuilder::with_capacity(3);
+        for (x, visible) in [(2, true), (0, false), (5, true)] {
+            a.push(Some(x));
+            mask.push(Some(visible));
+        }
+        let b = StringArray::from_slice(&[Some("x"), Some("y"), Some("z")]);
+        let chunk = DataChunk::try_new(schema, vec![a.finish().into(), b.into()]).unwrap();
+        assert!(expr.eval_chunk(&chunk).is_err());
+
+        let chunk = chunk.filter(&mask.finish()).unwrap();
+        let result = expr.eval_chunk(&chunk).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(5)));
+        assert_eq!(result.get(1), None);
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Int32(2)));
+
+        let constant = ExprNode::Literal(ScalarImpl::Int32(1))
+            .bind(&[DataType::Integer, DataType::Varchar])
+            .unwrap();
+        let result = constant.eval_chunk(&chunk).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get(1), None);
+
+        // A function of nulls still yields null for invisible rows.
+        let coalesce = call(
+            "coalesce",
+            vec![
+                ExprNode::InputRef(1),
+                ExprNode::Literal(ScalarImpl::String("w".into())),
+            ],
+        )
+        .bind(&[DataType::Integer, DataType::Varchar])
+        .unwrap();
+        let result = coalesce.eval_chunk(&chunk).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("x")));
+        assert_eq!(result.get(1), None);
+    }
+}
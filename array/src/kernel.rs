@@ -0,0 +1,355 @@
+//! Kernels selecting and combining the rows of arrays.
+
+use bitvec::slice::BitSlice;
+
+use crate::{prelude::BooleanArray, Array, ArrayBuilder, ArrayError, ArrayImpl, Result};
+
+/// Row selection, masking and concatenation, specialized by arrays that can copy their buffers
+/// directly.
+///
+/// The default implementations go through the array builder, one value at a time.
+pub(crate) trait Kernel: Array {
+    /// The rows at `indices`, which must be in bounds.
+    fn gather(&self, indices: &[usize]) -> Self {
+        let mut builder = self.new_builder(indices.len());
+        for &index in indices {
+            builder.push(self.get(index));
+        }
+        builder.finish()
+    }
+
+    /// The rows of this array, with those whose bit is unset in `mask` turned into nulls.
+    /// `mask` must have a bit for every row.
+    fn mask(&self, mask: &BitSlice) -> Self {
+        let mut builder = self.new_builder(self.len());
+        for (index, keep) in mask.iter().by_vals().enumerate() {
+            builder.push(if keep { self.get(index) } else { None });
+        }
+        builder.finish()
+    }
+
+    /// The rows of all `arrays`, end to end.
+    ///
+    /// Fails if the nested types or the values of an array don't fit the ones before it.
+    fn concat(arrays: &[&Self]) -> Result<Self> {
+        let len = arrays.iter().map(|array| array.len()).sum();
+        let mut builder = Self::Builder::with_capacity(len);
+        for array in arrays {
+            builder.check(array)?;
+            builder.append_array(array);
+        }
+        Ok(builder.finish())
+    }
+}
+
+/// Keep the rows of `array` where `mask` is true. Null counts as false.
+pub fn filter(array: &ArrayImpl, mask: &BooleanArray) -> Result<ArrayImpl> {
+    if array.len() != mask.len() {
+        return Err(ArrayError::LengthMismatch {
+            left: array.len(),
+            right: mask.len(),
+        });
+    }
+    let indices: Vec<_> = mask.true_bits().iter_ones().collect();
+    Ok(gather(array, &indices))
+}
+
+/// Gather the rows of `array` at `indices`, in that order. An index may appear several times.
+pub fn take(array: &ArrayImpl, indices: &[usize]) -> Result<ArrayImpl> {
+    if let Some(index) = indices.iter().find(|&&index| index >= array.len()) {
+        return Err(ArrayError::InvalidArgument(format!(
+            "index {} out of bounds for {} rows",
+            index,
+            array.len()
+        )));
+    }
+    Ok(gather(array, indices))
+}
+
+/// Gather the rows of `array` at `indices`, which must be in bounds.
+pub(crate) fn gather(array: &ArrayImpl, indices: &[usize]) -> ArrayImpl {
+    for_each_array!(array, array => array.gather(indices))
+}
+
+/// Turn the rows of `array` whose bit is unset in `mask` into nulls, which must have a bit for
+/// every row. Only the validity is rebuilt for arrays of non-nested values.
+pub(crate) fn mask(array: &ArrayImpl, mask: &BitSlice) -> ArrayImpl {
+    for_each_array!(array, array => array.mask(mask))
+}
+
+/// Join `arrays` end to end. They must all have the same type, including the types of nested
+/// values.
+///
+/// Fails if `arrays` is empty, as there is no type to give the result.
+pub fn concat(arrays: &[&ArrayImpl]) -> Result<ArrayImpl> {
+    let first = arrays
+        .first()
+        .ok_or_else(|| ArrayError::InvalidArgument("concat of no arrays".to_string()))?;
+    Ok(for_each_array!(first, first => concat_as(first, arrays)?))
+}
+
+/// Concatenate `arrays`, which must have the same type as `first`.
+fn concat_as<A>(_first: &A, arrays: &[&ArrayImpl]) -> Result<A>
+where
+    A: Kernel,
+    for<'a> &'a A: TryFrom<&'a ArrayImpl, Error = ArrayError>,
+{
+    let arrays = arrays
+        .iter()
+        .map(|&array| <&A>::try_from(array))
+        .collect::<Result<Vec<_>>>()?;
+    A::concat(&arrays)
+}
+
+/// The SQL `AND` of `left` and `right`: false if either side is false, otherwise null if either
+/// side is null.
+pub fn and(left: &BooleanArray, right: &BooleanArray) -> Result<BooleanArray> {
+    check_len(left, right)?;
+    Ok(left.zip_words(right, |l, l_valid, r, r_valid| {
+        let valid = (l_valid & r_valid) | (l_valid & !l) | (r_valid & !r);
+        (l & r, valid)
+    }))
+}
+
+/// The SQL `OR` of `left` and `right`: true if either side is true, otherwise null if either
+/// side is null.
+pub fn or(left: &BooleanArray, right: &BooleanArray) -> Result<BooleanArray> {
+    check_len(left, right)?;
+    Ok(left.zip_words(right, |l, l_valid, r, r_valid| {
+        let valid = (l_valid & r_valid) | (l_valid & l) | (r_valid & r);
+        (l | r, valid)
+    }))
+}
+
+/// The exclusive or of `left` and `right`, which is null if either side is null.
+pub fn xor(left: &BooleanArray, right: &BooleanArray) -> Result<BooleanArray> {
+    check_len(left, right)?;
+    Ok(left.zip_words(right, |l, l_valid, r, r_valid| (l ^ r, l_valid & r_valid)))
+}
+
+/// The SQL `NOT` of `array`, which keeps nulls.
+pub fn not(array: &BooleanArray) -> BooleanArray {
+    array.map_words(|value| !value)
+}
+
+fn check_len(left: &BooleanArray, right: &BooleanArray) -> Result<()> {
+    if left.len() != right.len() {
+        return Err(ArrayError::LengthMismatch {
+            left: left.len(),
+            right: right.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        array_impl::{ListArrayBuilder, ListValue, StringArray, StructArray},
+        prelude::Int32Array,
+        Scalar, ScalarRefImpl,
+    };
+
+    fn ints(values: &[Option<i32>]) -> ArrayImpl {
+        Int32Array::from_slice(values).into()
+    }
+
+    fn strings(values: &[Option<&str>]) -> ArrayImpl {
+        StringArray::from_slice(values).into()
+    }
+
+    fn values(array: &ArrayImpl) -> Vec<Option<ScalarRefImpl<'_>>> {
+        (0..array.len()).map(|i| array.get(i)).collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let mask = BooleanArray::from_slice(&[Some(true), Some(false), None, Some(true)]);
+
+        let result = filter(&ints(&[Some(1), Some(2), Some(3), None]), &mask).unwrap();
+        assert_eq!(values(&result), [Some(ScalarRefImpl::Int32(1)), None]);
+
+        let result = filter(
+            &strings(&[Some("a"), Some("b"), Some("c"), Some("")]),
+            &mask,
+        );
+        assert_eq!(
+            values(&result.unwrap()),
+            [
+                Some(ScalarRefImpl::String("a")),
+                Some(ScalarRefImpl::String(""))
+            ]
+        );
+
+        let err = filter(&ints(&[Some(1)]), &mask).unwrap_err();
+        assert!(matches!(err, ArrayError::LengthMismatch { .. }));
+    }
+
+    #[test]
+    fn test_take() {
+        let array = strings(&[Some("a"), None, Some("bc")]);
+        let result = take(&array, &[2, 2, 1, 0]).unwrap();
+        assert_eq!(
+            values(&result),
+            [
+                Some(ScalarRefImpl::String("bc")),
+                Some(ScalarRefImpl::String("bc")),
+                None,
+                Some(ScalarRefImpl::String("a"))
+            ]
+        );
+        assert_eq!(take(&array, &[]).unwrap().len(), 0);
+        assert!(take(&array, &[3]).is_err());
+
+        let result = take(&ints(&[Some(1), None]), &[1, 0]).unwrap();
+        assert_eq!(values(&result), [None, Some(ScalarRefImpl::Int32(1))]);
+    }
+
+    #[test]
+    fn test_concat() {
+        let a = ints(&[Some(1), None]);
+        let b = ints(&[Some(3)]);
+        let result = concat(&[&a, &b]).unwrap();
+        assert_eq!(
+            values(&result),
+            [
+                Some(ScalarRefImpl::Int32(1)),
+                None,
+                Some(ScalarRefImpl::Int32(3))
+            ]
+        );
+
+        let a = strings(&[Some("ab"), None]);
+        let b = strings(&[Some("c")]);
+        let result = concat(&[&a, &b, &a]).unwrap();
+        assert_eq!(
+            values(&result),
+            [
+                Some(ScalarRefImpl::String("ab")),
+                None,
+                Some(ScalarRefImpl::String("c")),
+                Some(ScalarRefImpl::String("ab")),
+                None
+            ]
+        );
+
+        assert!(matches!(
+            concat(&[&a, &ints(&[])]),
+            Err(ArrayError::TypeMismatch { .. })
+        ));
+        assert!(concat(&[]).is_err());
+    }
+
+    #[test]
+    fn test_sliced() {
+        let a = strings(&[Some("a"), Some("bc"), None, Some("d")]).slice(1, 2);
+        let b = ints(&[Some(1), None, Some(3)]).slice(1, 2);
+        let result = concat(&[&a, &a.slice(0, 1)]).unwrap();
+        assert_eq!(
+            values(&result),
+            [
+                Some(ScalarRefImpl::String("bc")),
+                None,
+                Some(ScalarRefImpl::String("bc"))
+            ]
+        );
+        let result = take(&b, &[1, 0]).unwrap();
+        assert_eq!(values(&result), [Some(ScalarRefImpl::Int32(3)), None]);
+        assert!(take(&b, &[2]).is_err());
+    }
+
+    #[test]
+    fn test_logic() {
+        let (t, f) = (Some(true), Some(false));
+        let left = BooleanArray::from_slice(&[t, t, t, f, f, f, None, None, None]);
+        let right = BooleanArray::from_slice(&[t, f, None, t, f, None, t, f, None]);
+        let values = |array: BooleanArray| array.iter().collect::<Vec<_>>();
+
+        let result = and(&left, &right).unwrap();
+        assert_eq!(values(result), [t, f, None, f, f, f, None, f, None]);
+        let result = or(&left, &right).unwrap();
+        assert_eq!(values(result), [t, t, t, t, f, None, t, None, None]);
+        let result = xor(&left, &right).unwrap();
+        assert_eq!(values(result), [f, t, None, t, f, None, None, None, None]);
+        assert_eq!(values(not(&right)), [f, t, None, f, t, None, f, t, None]);
+
+        let err = and(&left, &right.slice(0, 1)).unwrap_err();
+        assert!(matches!(err, ArrayError::LengthMismatch { .. }));
+    }
+
+    #[test]
+    fn test_logic_words() {
+        let left: Vec<_> = (0..200)
+            .map(|i| (i % 5 != 0).then_some(i % 2 == 0))
+            .collect();
+        let right: Vec<_> = (0..200).map(|i| Some(i % 3 == 0)).collect();
+        let (l, r) = (&left[7..150], &right[50..193]);
+        let left = BooleanArray::from_slice(&left).slice(7, 143);
+        let right = BooleanArray::from_slice(&right).slice(50, 143);
+
+        let expected: Vec<_> = l
+            .iter()
+            .zip(r)
+            .map(|(l, r)| match (l, r) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            })
+            .collect();
+        let result = and(&left, &right).unwrap();
+        assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+
+        let expected: Vec<_> = l.iter().map(|l| l.map(|l| !l)).collect();
+        assert_eq!(not(&left).iter().collect::<Vec<_>>(), expected);
+        assert!(xor(&right, &right).unwrap().validity().all_valid());
+    }
+
+    #[test]
+    fn test_nested() {
+        let rows = StructArray::new(vec![
+            ("id".to_string(), ints(&[Some(1), Some(2)])),
+            ("name".to_string(), strings(&[Some("a"), None])),
+        ]);
+        let rows: ArrayImpl = rows.into();
+        let result = take(&rows, &[1, 1]).unwrap();
+        let result = concat(&[&rows, &result]).unwrap();
+        assert_eq!(result.len(), 4);
+        match result.get(3) {
+            Some(ScalarRefImpl::Struct(row)) => {
+                assert_eq!(row.field(0), Some(ScalarRefImpl::Int32(2)));
+                assert_eq!(row.field(1), None);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let other: ArrayImpl = StructArray::new(vec![
+            ("id".to_string(), strings(&[Some("1")])),
+            ("name".to_string(), strings(&[Some("b")])),
+        ])
+        .into();
+        assert!(matches!(
+            concat(&[&rows, &other]),
+            Err(ArrayError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_nested_list_mismatch() {
+        let list = |element: ArrayImpl| -> ArrayImpl {
+            let mut builder = ListArrayBuilder::with_capacity(1);
+            builder.push(Some(ListValue::new(element).as_scalar_ref()));
+            builder.finish().into()
+        };
+        let ints = list(ints(&[Some(1)]));
+        let result = concat(&[&ints, &ints]).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            concat(&[&ints, &list(strings(&[Some("a")]))]).unwrap_err(),
+            ArrayError::TypeMismatch {
+                expected: "Int32Array",
+                actual: "StringArray",
+            }
+        );
+    }
+}
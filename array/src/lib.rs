@@ -1,3 +1,6 @@
+// Error definition
+mod error;
+
 // Trait definition
 mod array;
 mod data_type;
@@ -5,36 +8,79 @@ mod scalar;
 
 // Trait implementaion
 mod array_impl;
+mod bitmap;
 mod scalar_impl;
 
+// Scalar value types
+mod datetime;
+mod decimal;
+
 // Dispatch implementation
+#[macro_use]
 mod dispatch;
 
+// Column batches
+mod data_chunk;
+pub mod kernel;
+mod schema;
+
 /// Expression definition and implementation
 mod expression;
+mod expression_tree;
+#[macro_use]
 mod expression_impl;
-
-mod test;
+mod cast;
+mod registry;
+mod signature;
 
 pub use array::*;
+pub use array_macros::function;
+pub use bitmap::Bitmap;
+pub use cast::*;
+pub use data_chunk::DataChunk;
 pub use data_type::DataType;
+pub use datetime::{Date, Interval, Time, Timestamp};
+pub use decimal::Decimal;
+pub use dispatch::{ArrayBuilderImpl, ArrayImpl, ScalarImpl, ScalarRefImpl};
+pub use error::{ArrayError, Result};
 pub use expression::*;
+pub use expression_impl::*;
+pub use expression_tree::*;
+pub use registry::*;
 pub use scalar::*;
+pub use schema::{Field, Schema, SchemaRef};
+pub use signature::*;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use linkme;
+}
 
 pub mod prelude {
+    pub use crate::array_impl::{
+        BooleanArray, BooleanArrayBuilder, DecimalArray, DecimalArrayBuilder,
+    };
+
     use crate::array_impl::{PrimitiveArray, PrimitiveArrayBuilder};
+    use crate::{Date, Interval, Time, Timestamp};
 
     pub type Int16Array = PrimitiveArray<i16>;
     pub type Int32Array = PrimitiveArray<i32>;
     pub type Int64Array = PrimitiveArray<i64>;
     pub type Float32Array = PrimitiveArray<f32>;
     pub type Float64Array = PrimitiveArray<f64>;
-    pub type BooleanArray = PrimitiveArray<bool>;
+    pub type DateArray = PrimitiveArray<Date>;
+    pub type TimeArray = PrimitiveArray<Time>;
+    pub type TimestampArray = PrimitiveArray<Timestamp>;
+    pub type IntervalArray = PrimitiveArray<Interval>;
 
     pub type Int16ArrayBuilder = PrimitiveArrayBuilder<i16>;
     pub type Int32ArrayBuilder = PrimitiveArrayBuilder<i32>;
     pub type Int64ArrayBuilder = PrimitiveArrayBuilder<i64>;
     pub type Float32ArrayBuilder = PrimitiveArrayBuilder<f32>;
     pub type Float64ArrayBuilder = PrimitiveArrayBuilder<f64>;
-    pub type BooleanArrayBuilder = PrimitiveArrayBuilder<bool>;
+    pub type DateArrayBuilder = PrimitiveArrayBuilder<Date>;
+    pub type TimeArrayBuilder = PrimitiveArrayBuilder<Time>;
+    pub type TimestampArrayBuilder = PrimitiveArrayBuilder<Timestamp>;
+    pub type IntervalArrayBuilder = PrimitiveArrayBuilder<Interval>;
 }
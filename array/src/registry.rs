@@ -0,0 +1,107 @@
+use std::mem::discriminant;
+
+use linkme::distributed_slice;
+
+use crate::{DataType, Expression};
+
+/// A function registered with the [`function`](crate::function) attribute.
+pub struct FunctionDescriptor {
+    pub name: &'static str,
+    pub args: &'static [DataType],
+    pub ret: DataType,
+    /// Build an expression evaluating the function.
+    pub build: fn() -> Box<dyn Expression>,
+}
+
+/// All functions registered with the [`function`](crate::function) attribute, from any crate.
+#[distributed_slice]
+pub static FUNCTIONS: [FunctionDescriptor];
+
+/// Find the registered function `name` taking `args`.
+///
+/// Parameters of a type, such as the scale of `Decimal`, are ignored.
+pub fn lookup_function(name: &str, args: &[DataType]) -> Option<&'static FunctionDescriptor> {
+    FUNCTIONS.iter().find(|function| {
+        function.name == name
+            && function.args.len() == args.len()
+            && function
+                .args
+                .iter()
+                .zip(args)
+                .all(|(param, arg)| discriminant(param) == discriminant(arg))
+    })
+}
+
+/// The name of a registered function, as stored in the registry.
+pub(crate) fn function_name(name: &str) -> Option<&'static str> {
+    FUNCTIONS
+        .iter()
+        .find(|function| function.name == name)
+        .map(|function| function.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        array_impl::StringArray, build_expression, function, ArrayError, ArrayImpl, Datum,
+        ExpressionType, Result, ScalarRefImpl,
+    };
+
+    #[function("test_add(int32, int64) -> int64")]
+    fn test_add(a: i32, b: i64) -> Result<i64> {
+        (a as i64)
+            .checked_add(b)
+            .ok_or_else(|| ArrayError::Overflow(format!("{} + {}", a, b)))
+    }
+
+    #[function("test_repeat(varchar, int32) -> varchar")]
+    fn test_repeat(s: &str, n: i32) -> String {
+        s.repeat(n.max(0) as usize)
+    }
+
+    #[test]
+    fn test_lookup() {
+        let function = lookup_function("test_add", &[DataType::Integer, DataType::BigInt]).unwrap();
+        assert_eq!(function.ret, DataType::BigInt);
+        assert!(lookup_function("test_add", &[DataType::Integer, DataType::Integer]).is_none());
+        assert!(lookup_function("test_sub", &[DataType::Integer, DataType::BigInt]).is_none());
+    }
+
+    #[test]
+    fn test_build_registered() {
+        let expr = build_expression(
+            ExpressionType::Function("test_repeat"),
+            &[DataType::Varchar, DataType::Integer],
+        )
+        .unwrap();
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("ab"), None]).into();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&strings),
+                Datum::Scalar(Some(ScalarRefImpl::Int32(2))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("abab")));
+        assert_eq!(result.get(1), None);
+
+        let expr = build_expression(
+            ExpressionType::Function("test_add"),
+            &[DataType::Integer, DataType::BigInt],
+        )
+        .unwrap();
+        let err = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int32(1))),
+                Datum::Scalar(Some(ScalarRefImpl::Int64(i64::MAX))),
+            ])
+            .unwrap_err();
+        assert!(matches!(err, ArrayError::Overflow(_)));
+
+        assert!(build_expression(
+            ExpressionType::Function("test_add"),
+            &[DataType::Varchar, DataType::BigInt],
+        )
+        .is_err());
+    }
+}
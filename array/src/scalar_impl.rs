@@ -1,6 +1,6 @@
 use crate::{
-    array_impl::{PrimitiveArray, StringArray},
-    PrimitiveType, Scalar, ScalarRef,
+    array_impl::{BooleanArray, BytesArray, DecimalArray, PrimitiveArray, StringArray},
+    Date, Decimal, Interval, PrimitiveType, Scalar, ScalarRef, Time, Timestamp,
 };
 
 impl<T: PrimitiveType> Scalar for T {
@@ -29,7 +29,52 @@ impl PrimitiveType for i32 {}
 impl PrimitiveType for i64 {}
 impl PrimitiveType for f32 {}
 impl PrimitiveType for f64 {}
-impl PrimitiveType for bool {}
+impl PrimitiveType for Date {}
+impl PrimitiveType for Time {}
+impl PrimitiveType for Timestamp {}
+impl PrimitiveType for Interval {}
+
+impl Scalar for bool {
+    type RefType<'a> = bool;
+    type ArrayTpye = BooleanArray;
+
+    fn as_scalar_ref<'a>(&'a self) -> Self::RefType<'a> {
+        *self
+    }
+    fn upcast_to<'a, 'b: 'a>(ref_item: Self::RefType<'b>) -> Self::RefType<'a> {
+        ref_item
+    }
+}
+
+impl<'a> ScalarRef<'a> for bool {
+    type ScalarType = bool;
+    type ArrayType = BooleanArray;
+
+    fn to_scalar_owned(&self) -> Self::ScalarType {
+        *self
+    }
+}
+
+impl Scalar for Decimal {
+    type RefType<'a> = Decimal;
+    type ArrayTpye = DecimalArray;
+
+    fn as_scalar_ref<'a>(&'a self) -> Self::RefType<'a> {
+        *self
+    }
+    fn upcast_to<'a, 'b: 'a>(ref_item: Self::RefType<'b>) -> Self::RefType<'a> {
+        ref_item
+    }
+}
+
+impl<'a> ScalarRef<'a> for Decimal {
+    type ScalarType = Decimal;
+    type ArrayType = DecimalArray;
+
+    fn to_scalar_owned(&self) -> Self::ScalarType {
+        *self
+    }
+}
 
 impl Scalar for String {
     type RefType<'a> = &'a str;
@@ -37,7 +82,7 @@ impl Scalar for String {
     type ArrayTpye = StringArray;
 
     fn as_scalar_ref<'a>(&'a self) -> Self::RefType<'a> {
-        &self
+        self
     }
     fn upcast_to<'a, 'b: 'a>(ref_item: Self::RefType<'b>) -> Self::RefType<'a> {
         ref_item
@@ -53,3 +98,26 @@ impl<'a> ScalarRef<'a> for &'a str {
         self.to_string()
     }
 }
+
+impl Scalar for Vec<u8> {
+    type RefType<'a> = &'a [u8];
+
+    type ArrayTpye = BytesArray;
+
+    fn as_scalar_ref<'a>(&'a self) -> Self::RefType<'a> {
+        self
+    }
+    fn upcast_to<'a, 'b: 'a>(ref_item: Self::RefType<'b>) -> Self::RefType<'a> {
+        ref_item
+    }
+}
+
+impl<'a> ScalarRef<'a> for &'a [u8] {
+    type ScalarType = Vec<u8>;
+
+    type ArrayType = BytesArray;
+
+    fn to_scalar_owned(&self) -> Self::ScalarType {
+        self.to_vec()
+    }
+}
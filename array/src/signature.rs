@@ -0,0 +1,591 @@
+use std::mem::discriminant;
+
+use crate::{
+    build_cast, build_expression,
+    decimal::{MAX_PRECISION, MAX_SCALE, MIN_DIV_SCALE},
+    ArrayError, ArrayImpl, DataType, Datum, Expression, ExpressionType, Result, FUNCTIONS,
+};
+
+/// The argument and return types of one overload of a function.
+///
+/// Parameters of a type, such as the width of `Char`, are ignored when matching arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSignature {
+    pub func: ExpressionType,
+    pub args: Vec<DataType>,
+    /// Whether the last argument can be repeated any number of times.
+    pub variadic: bool,
+    pub ret: DataType,
+}
+
+impl FunctionSignature {
+    pub fn new(func: ExpressionType, args: Vec<DataType>, ret: DataType) -> Self {
+        Self {
+            func,
+            args,
+            variadic: false,
+            ret,
+        }
+    }
+
+    pub fn variadic(func: ExpressionType, arg: DataType, ret: DataType) -> Self {
+        Self {
+            func,
+            args: vec![arg],
+            variadic: true,
+            ret,
+        }
+    }
+
+    /// The parameter type of each of `arity` arguments, or `None` if the arity doesn't match.
+    fn params(&self, arity: usize) -> Option<Vec<&DataType>> {
+        if self.variadic {
+            let (last, fixed) = self.args.split_last()?;
+            if arity < self.args.len() {
+                return None;
+            }
+            Some(
+                fixed
+                    .iter()
+                    .chain(std::iter::repeat_n(last, arity - fixed.len()))
+                    .collect(),
+            )
+        } else if arity == self.args.len() {
+            Some(self.args.iter().collect())
+        } else {
+            None
+        }
+    }
+}
+
+/// The overload of a function chosen for some argument types by [`FunctionCatalog::resolve`].
+#[derive(Debug)]
+pub struct ResolvedFunction<'a> {
+    pub signature: &'a FunctionSignature,
+    /// The type each argument is implicitly cast to, or `None` if it is passed as is.
+    pub casts: Vec<Option<DataType>>,
+}
+
+/// The signatures of all functions that can be built.
+#[derive(Debug, Default)]
+pub struct FunctionCatalog {
+    signatures: Vec<FunctionSignature>,
+}
+
+impl FunctionCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A catalog with the signatures of all functions supported by [`build_expression`].
+    pub fn builtin() -> Self {
+        use DataType::*;
+        use ExpressionType::*;
+
+        let numeric = [
+            SmallInt,
+            Integer,
+            BigInt,
+            Real,
+            Double,
+            Decimal {
+                scale: 0,
+                precision: 38,
+            },
+        ];
+        let signed = numeric.clone();
+        let comparable = [
+            Boolean,
+            SmallInt,
+            Integer,
+            BigInt,
+            Real,
+            Double,
+            Decimal {
+                scale: 0,
+                precision: 38,
+            },
+            Date,
+            Time,
+            Timestamp { timezone: None },
+            Interval,
+            Varchar,
+            Bytea,
+        ];
+
+        let mut catalog = Self::new();
+        for func in [
+            CmpEq,
+            CmpNe,
+            CmpLt,
+            CmpLe,
+            CmpGt,
+            CmpGe,
+            IsDistinctFrom,
+            IsNotDistinctFrom,
+        ] {
+            for ty in &comparable {
+                catalog.register(FunctionSignature::new(
+                    func,
+                    vec![ty.clone(), ty.clone()],
+                    Boolean,
+                ));
+            }
+        }
+        for func in [Add, Sub, Mul, Div, Mod] {
+            for ty in &numeric {
+                catalog.register(FunctionSignature::new(
+                    func,
+                    vec![ty.clone(), ty.clone()],
+                    ty.clone(),
+                ));
+            }
+        }
+        for func in [Neg, Abs] {
+            for ty in &signed {
+                catalog.register(FunctionSignature::new(func, vec![ty.clone()], ty.clone()));
+            }
+        }
+        for ty in &comparable {
+            catalog.register(FunctionSignature::variadic(
+                Coalesce,
+                ty.clone(),
+                ty.clone(),
+            ));
+        }
+        catalog.register(FunctionSignature::new(Not, vec![Boolean], Boolean));
+        catalog.register(FunctionSignature::new(Length, vec![Varchar], Integer));
+        catalog.register(FunctionSignature::new(Upper, vec![Varchar], Varchar));
+        catalog.register(FunctionSignature::new(Lower, vec![Varchar], Varchar));
+        catalog.register(FunctionSignature::new(
+            ConstainsStr,
+            vec![Varchar, Varchar],
+            Boolean,
+        ));
+        catalog.register(FunctionSignature::new(
+            Substr,
+            vec![Varchar, Integer, Integer],
+            Varchar,
+        ));
+        catalog.register(FunctionSignature::new(
+            Replace,
+            vec![Varchar, Varchar, Varchar],
+            Varchar,
+        ));
+        catalog.register(FunctionSignature::variadic(Concat, Varchar, Varchar));
+        for function in FUNCTIONS {
+            catalog.register(FunctionSignature::new(
+                Function(function.name),
+                function.args.to_vec(),
+                function.ret.clone(),
+            ));
+        }
+        catalog
+    }
+
+    pub fn register(&mut self, signature: FunctionSignature) {
+        self.signatures.push(signature);
+    }
+
+    /// All overloads of `func`.
+    pub fn signatures(&self, func: ExpressionType) -> impl Iterator<Item = &FunctionSignature> {
+        self.signatures.iter().filter(move |sig| sig.func == func)
+    }
+
+    /// Choose the overload of `func` that needs the cheapest implicit casts for `args`.
+    ///
+    /// If several overloads are equally cheap, the one registered first is chosen.
+    pub fn resolve(&self, func: ExpressionType, args: &[DataType]) -> Result<ResolvedFunction<'_>> {
+        let mut best: Option<(u32, ResolvedFunction<'_>)> = None;
+        for signature in self.signatures(func) {
+            let Some(params) = signature.params(args.len()) else {
+                continue;
+            };
+            let mut cost = 0;
+            let mut casts = Vec::with_capacity(args.len());
+            let coercible = args.iter().zip(params).all(|(arg, param)| {
+                match implicit_cast_cost(arg, param) {
+                    Some(0) => casts.push(None),
+                    Some(c) => {
+                        cost += c;
+                        casts.push(Some(param.clone()));
+                    }
+                    None => return false,
+                }
+                true
+            });
+            if coercible && best.as_ref().is_none_or(|(best, _)| cost < *best) {
+                best = Some((cost, ResolvedFunction { signature, casts }));
+            }
+        }
+        best.map(|(_, resolved)| resolved).ok_or_else(|| {
+            ArrayError::Unsupported(format!("no overload of {:?} for {:?}", func, args))
+        })
+    }
+
+    /// Build `func` over `args`, inserting implicit casts as needed.
+    /// Returns the expression and its return type, whose parameters are derived from the
+    /// arguments for builtin functions of decimals and timestamps.
+    pub fn build(
+        &self,
+        func: ExpressionType,
+        args: &[DataType],
+    ) -> Result<(Box<dyn Expression>, DataType)> {
+        let resolved = self.resolve(func, args)?;
+        let mut casts = Vec::with_capacity(args.len());
+        let mut arg_types = Vec::with_capacity(args.len());
+        for (arg, cast) in args.iter().zip(resolved.casts) {
+            match cast {
+                Some(to) => {
+                    casts.push(Some(build_cast(arg, &to)?));
+                    arg_types.push(to);
+                }
+                None => {
+                    casts.push(None);
+                    arg_types.push(arg.clone());
+                }
+            }
+        }
+        let inner = build_expression(func, &arg_types)?;
+        let ret = return_type(resolved.signature, &arg_types);
+        if casts.iter().all(Option::is_none) {
+            return Ok((inner, ret));
+        }
+        Ok((Box::new(ImplicitCastExpression { casts, inner }), ret))
+    }
+}
+
+/// The return type of `signature` for arguments of `args` types, after implicit casts.
+fn return_type(signature: &FunctionSignature, args: &[DataType]) -> DataType {
+    let ret = &signature.ret;
+    if matches!(signature.func, ExpressionType::Function(_)) {
+        return ret.clone();
+    }
+    match ret {
+        DataType::Decimal { .. } => decimal_return_type(signature.func, args),
+        DataType::Timestamp { .. } => args
+            .iter()
+            .find(|arg| discriminant(*arg) == discriminant(ret))
+            .cloned(),
+        _ => None,
+    }
+    .unwrap_or_else(|| ret.clone())
+}
+
+/// The type of the result of `func` over decimals of `args` types, with the scale of the
+/// result of the matching [`Decimal`](crate::Decimal) operation, and enough precision for any
+/// value up to [`MAX_PRECISION`].
+fn decimal_return_type(func: ExpressionType, args: &[DataType]) -> Option<DataType> {
+    // The scale and number of integral digits of each argument.
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            DataType::Decimal { scale, precision } => {
+                Some((*scale, precision.saturating_sub(*scale)))
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let (scale, integral) = match (func, &args[..]) {
+        (ExpressionType::Add | ExpressionType::Sub, &[(s1, i1), (s2, i2)]) => {
+            (s1.max(s2), i1.max(i2) + 1)
+        }
+        (ExpressionType::Mul, &[(s1, i1), (s2, i2)]) => (s1 + s2, i1 + i2),
+        (ExpressionType::Div, &[(s1, i1), (s2, _)]) => {
+            (s1.max(s2).max(MIN_DIV_SCALE as u16), i1 + s2)
+        }
+        (ExpressionType::Mod, &[(s1, i1), (s2, i2)]) => (s1.max(s2), i1.min(i2)),
+        (ExpressionType::Neg | ExpressionType::Abs, &[(s, i)]) => (s, i),
+        (ExpressionType::Coalesce, args) => args
+            .iter()
+            .copied()
+            .reduce(|(s1, i1), (s2, i2)| (s1.max(s2), i1.max(i2)))?,
+        _ => return None,
+    };
+    let scale = scale.min(MAX_SCALE as u16);
+    Some(DataType::Decimal {
+        scale,
+        precision: (integral + scale).clamp(1, MAX_PRECISION as u16),
+    })
+}
+
+/// The cost of implicitly casting `from` to `to`, or `None` if it needs an explicit cast.
+///
+/// Numbers are only widened along `smallint, integer, bigint, decimal, real, double`, and each
+/// step costs 1. Decimals are never implicitly cast to floats, nor integers and bigints to
+/// reals, since those casts lose precision. Values of the same type, whatever its parameters,
+/// are passed as is.
+pub fn implicit_cast_cost(from: &DataType, to: &DataType) -> Option<u32> {
+    fn numeric_rank(data_type: &DataType) -> Option<u32> {
+        match data_type {
+            DataType::SmallInt => Some(0),
+            DataType::Integer => Some(1),
+            DataType::BigInt => Some(2),
+            DataType::Decimal { .. } => Some(3),
+            DataType::Real => Some(4),
+            DataType::Double => Some(5),
+            _ => None,
+        }
+    }
+
+    if discriminant(from) == discriminant(to) {
+        return Some(0);
+    }
+    match (from, to) {
+        (DataType::Char { .. }, DataType::Varchar) => Some(1),
+        (DataType::Date, DataType::Timestamp { .. }) => Some(1),
+        (DataType::Decimal { .. }, DataType::Real | DataType::Double) => None,
+        (DataType::Integer | DataType::BigInt, DataType::Real) => None,
+        _ => {
+            let (from, to) = (numeric_rank(from)?, numeric_rank(to)?);
+            (from < to).then(|| to - from)
+        }
+    }
+}
+
+/// Casts some inputs before evaluating `inner`.
+struct ImplicitCastExpression {
+    casts: Vec<Option<Box<dyn Expression>>>,
+    inner: Box<dyn Expression>,
+}
+
+impl Expression for ImplicitCastExpression {
+    fn eval_datums(&self, inputs: &[Datum<'_>]) -> Result<ArrayImpl> {
+        if inputs.len() != self.casts.len() {
+            return Err(ArrayError::ArityMismatch {
+                expected: self.casts.len(),
+                actual: inputs.len(),
+            });
+        }
+        let casted = inputs
+            .iter()
+            .zip(&self.casts)
+            .map(|(input, cast)| cast.as_ref().map(|cast| cast.eval_datums(&[*input])))
+            .map(Option::transpose)
+            .collect::<Result<Vec<_>>>()?;
+        // A constant stays a constant, so that it is still broadcast.
+        let inputs: Vec<_> = inputs
+            .iter()
+            .zip(&casted)
+            .map(|(input, casted)| match (input, casted) {
+                (_, None) => *input,
+                (Datum::Array(_), Some(array)) => Datum::Array(array),
+                (Datum::Scalar(_), Some(array)) => Datum::Scalar(array.get(0)),
+            })
+            .collect();
+        self.inner.eval_datums(&inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        array_impl::StringArray,
+        prelude::{Int16ArrayBuilder, Int64ArrayBuilder},
+        ArrayBuilder, ScalarRefImpl,
+    };
+
+    #[test]
+    fn test_resolve() {
+        let catalog = FunctionCatalog::builtin();
+
+        let resolved = catalog
+            .resolve(
+                ExpressionType::CmpGe,
+                &[DataType::SmallInt, DataType::BigInt],
+            )
+            .unwrap();
+        assert_eq!(
+            resolved.signature.args,
+            [DataType::BigInt, DataType::BigInt]
+        );
+        assert_eq!(resolved.casts, [Some(DataType::BigInt), None]);
+
+        let resolved = catalog
+            .resolve(ExpressionType::Add, &[DataType::Integer, DataType::Double])
+            .unwrap();
+        assert_eq!(resolved.signature.ret, DataType::Double);
+
+        for int in [DataType::Integer, DataType::BigInt] {
+            let resolved = catalog
+                .resolve(ExpressionType::Add, &[int, DataType::Real])
+                .unwrap();
+            assert_eq!(resolved.signature.ret, DataType::Double);
+        }
+
+        let decimal = DataType::Decimal {
+            scale: 2,
+            precision: 10,
+        };
+        for args in [
+            [decimal.clone(), decimal.clone()],
+            [DataType::Integer, decimal.clone()],
+        ] {
+            let resolved = catalog.resolve(ExpressionType::Add, &args).unwrap();
+            assert!(matches!(resolved.signature.ret, DataType::Decimal { .. }));
+        }
+        assert!(catalog
+            .resolve(ExpressionType::Mul, &[decimal.clone(), DataType::Real])
+            .is_err());
+
+        let resolved = catalog
+            .resolve(
+                ExpressionType::Concat,
+                &[DataType::Char { width: 3 }, DataType::Varchar],
+            )
+            .unwrap();
+        assert_eq!(resolved.casts, [Some(DataType::Varchar), None]);
+
+        assert!(catalog
+            .resolve(ExpressionType::Add, &[DataType::Double, DataType::Varchar])
+            .is_err());
+        assert!(catalog
+            .resolve(ExpressionType::CmpEq, &[DataType::Integer])
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_with_casts() {
+        let catalog = FunctionCatalog::builtin();
+
+        let mut a = Int16ArrayBuilder::with_capacity(3);
+        let mut b = Int64ArrayBuilder::with_capacity(3);
+        for (x, y) in [
+            (Some(1), Some(0)),
+            (Some(1), Some(1 << 40)),
+            (None, Some(0)),
+        ] {
+            a.push(x);
+            b.push(y);
+        }
+        let (a, b): (ArrayImpl, ArrayImpl) = (a.finish().into(), b.finish().into());
+
+        let (expr, ret) = catalog
+            .build(
+                ExpressionType::CmpGe,
+                &[DataType::SmallInt, DataType::BigInt],
+            )
+            .unwrap();
+        assert_eq!(ret, DataType::Boolean);
+        let result = expr.eval_batch(&[&a, &b]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Boolean(true)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Boolean(false)));
+        assert_eq!(result.get(2), None);
+
+        let (expr, ret) = catalog
+            .build(ExpressionType::Add, &[DataType::SmallInt, DataType::Double])
+            .unwrap();
+        assert_eq!(ret, DataType::Double);
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&a),
+                Datum::Scalar(Some(ScalarRefImpl::Float64(0.5))),
+            ])
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Float64(1.5)));
+
+        // Agrees with `build_expression`, as an f32 doesn't hold every i32.
+        let (expr, ret) = catalog
+            .build(ExpressionType::Add, &[DataType::Integer, DataType::Real])
+            .unwrap();
+        assert_eq!(ret, DataType::Double);
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Int32(16_777_217))),
+                Datum::Scalar(Some(ScalarRefImpl::Float32(0.0))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Float64(16_777_217.0)));
+
+        let strings: ArrayImpl = StringArray::from_slice(&[Some("a"), None]).into();
+        let (expr, _) = catalog
+            .build(
+                ExpressionType::Coalesce,
+                &[DataType::Varchar, DataType::Char { width: 1 }],
+            )
+            .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::Array(&strings),
+                Datum::Scalar(Some(ScalarRefImpl::String("z"))),
+            ])
+            .unwrap();
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("z")));
+
+        let decimal = DataType::Decimal {
+            scale: 1,
+            precision: 10,
+        };
+        let (expr, ret) = catalog
+            .build(ExpressionType::Add, &[decimal.clone(), decimal.clone()])
+            .unwrap();
+        assert_eq!(
+            ret,
+            DataType::Decimal {
+                scale: 1,
+                precision: 11,
+            }
+        );
+        let result = expr
+            .eval_datums(&[
+                Datum::Scalar(Some(ScalarRefImpl::Decimal("0.1".parse().unwrap()))),
+                Datum::Scalar(Some(ScalarRefImpl::Decimal("0.2".parse().unwrap()))),
+            ])
+            .unwrap();
+        assert_eq!(
+            result.get(0),
+            Some(ScalarRefImpl::Decimal("0.3".parse().unwrap()))
+        );
+
+        let wide = DataType::Decimal {
+            scale: 3,
+            precision: 5,
+        };
+        for (func, scale, precision) in [
+            (ExpressionType::Mul, 4, 15),
+            (ExpressionType::Div, 6, 18),
+            (ExpressionType::Coalesce, 3, 12),
+        ] {
+            let (_, ret) = catalog
+                .build(func, &[decimal.clone(), wide.clone()])
+                .unwrap();
+            assert_eq!(ret, DataType::Decimal { scale, precision });
+        }
+        let (_, ret) = catalog
+            .build(ExpressionType::Add, &[DataType::Integer, decimal])
+            .unwrap();
+        assert_eq!(
+            ret,
+            DataType::Decimal {
+                scale: 1,
+                precision: 38,
+            }
+        );
+
+        let utc = DataType::Timestamp {
+            timezone: Some("UTC".to_string()),
+        };
+        let (_, ret) = catalog
+            .build(ExpressionType::Coalesce, &[utc.clone(), DataType::Date])
+            .unwrap();
+        assert_eq!(ret, utc);
+    }
+
+    #[test]
+    fn test_builtin_signatures_build() {
+        let catalog = FunctionCatalog::builtin();
+        for signature in &catalog.signatures {
+            let mut args = signature.args.clone();
+            if signature.variadic {
+                args.extend(signature.args.last().cloned());
+            }
+            assert!(
+                build_expression(signature.func, &args).is_ok(),
+                "{:?}",
+                signature
+            );
+        }
+    }
+}